@@ -1,45 +1,156 @@
 fn main() {
     use build_script_cfg::Cfg;
-    use search_infini_core::{find_infini_op, find_infini_rt};
-    use std::{env, path::PathBuf};
+    use search_infini_core::{search, BACKENDS};
+    use std::{env, fs, path::PathBuf};
 
     let cfg = Cfg::new("infini");
-    
-    // 使用环境变量中的INFINI_ROOT
-    let root = match env::var("INFINI_ROOT") {
-        Ok(path) => PathBuf::from(path),
-        Err(_) => {
-            // 如果环境变量不存在，尝试使用默认路径
-            let Some(default_root) = find_infini_rt() else {
-                panic!("INFINI_ROOT not set and could not find infinirt library");
-            };
-            default_root
-        }
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    println!("cargo:rerun-if-changed=bundled/bindings.rs");
+
+    // 库名可以通过环境变量覆盖，例如 INFINI_RT_LIB=infinirt-cuda
+    let lib_name = |key: &str, default: &str| {
+        println!("cargo:rerun-if-env-changed={key}");
+        env::var(key).unwrap_or_else(|_| default.into())
     };
+    let ccl = cfg!(feature = "ccl");
+    let mut headers = vec!["infinirt.h", "infiniop.h"];
+    let mut libs = vec![
+        lib_name("INFINI_RT_LIB", "infinirt"),
+        lib_name("INFINI_OP_LIB", "infiniop"),
+    ];
+    if ccl {
+        headers.push("infiniccl.h");
+        libs.push(lib_name("INFINI_CCL_LIB", "infiniccl"));
+    }
+
+    // 静态链接：`static` feature 或 INFINI_STATIC=1
+    let flag = |key: &str| {
+        println!("cargo:rerun-if-env-changed={key}");
+        env::var(key).is_ok_and(|v| !matches!(&*v, "" | "0" | "false"))
+    };
+    let link_static = cfg!(feature = "static") || flag("INFINI_STATIC");
+    // 可重定位部署时不写入安装目录的 rpath：INFINI_NO_RPATH=1
+    let rpath = !link_static && !flag("INFINI_NO_RPATH");
+
+    // 查找顺序参见 search_infini_core::search
+    let found = search(
+        &headers,
+        &libs.iter().map(String::as_str).collect::<Vec<_>>(),
+    );
 
-    let include = root.join("include");
-    let lib = root.join("lib");
-
-    cfg.define();
-    
-    // 添加库搜索路径
-    println!("cargo:rustc-link-search={}", lib.display());
-    
-    // 链接infinirt和infiniop库
-    println!("cargo:rustc-link-lib=infinirt");
-    println!("cargo:rustc-link-lib=infiniop");
-    
-    // 在非Windows系统上添加rpath
-    if !cfg!(windows) {
-        println!("cargo::rustc-link-arg=-Wl,-rpath,{}", lib.display());
+    // 运行时加载时 src/dl.rs 默认按这些名字和目录打开库
+    let dlopen = cfg!(feature = "dlopen") && !cfg!(feature = "cpu");
+    if dlopen {
+        println!("cargo:rustc-env=INFINI_DLOPEN_LIBS={}", libs.join(","));
     }
 
+    let mut backends = Vec::new();
+    match &found {
+        // 纯 Rust 后端自带 infinirt 符号，不链接 C 库
+        _ if cfg!(feature = "cpu") => backends.push("cpu"),
+        // 运行时加载，不链接 C 库，只记录构建时找到的库目录
+        Ok(infini) if dlopen => {
+            backends = infini.backends();
+            println!(
+                "cargo:rustc-env=INFINI_DLOPEN_DIR={}",
+                infini.lib_dir.display()
+            );
+        }
+        Err(_) if dlopen => {}
+        Ok(infini) => {
+            let lib = &infini.lib_dir;
+
+            cfg.define();
+            backends = infini.backends();
+
+            // 添加库搜索路径
+            println!("cargo:rustc-link-search=native={}", lib.display());
+
+            // 链接infinirt和infiniop库，启用 ccl 时还有infiniccl
+            let kind = if link_static { "static" } else { "dylib" };
+            for lib in &libs {
+                println!("cargo:rustc-link-lib={kind}={lib}");
+            }
+            // InfiniCore 由 C++ 实现，静态链接时需要 C++ 运行时
+            if link_static {
+                match env::var("CARGO_CFG_TARGET_OS").as_deref() {
+                    Ok("macos" | "ios") => println!("cargo:rustc-link-lib=c++"),
+                    Ok("windows") => {}
+                    _ => println!("cargo:rustc-link-lib=stdc++"),
+                }
+            }
+
+            // 在非Windows系统上添加rpath
+            if rpath && !cfg!(windows) {
+                println!("cargo::rustc-link-arg=-Wl,-rpath,{}", lib.display());
+            }
+
+            // 依赖此 crate 的构建脚本可以通过 DEP_INFINI_ROOT、DEP_INFINI_INCLUDE 和 DEP_INFINI_LIB 找到同一个安装
+            if let Some(root) = infini.root() {
+                println!("cargo:root={}", root.display());
+            }
+            println!("cargo:include={}", infini.include_dir.display());
+            println!("cargo:lib={}", lib.display());
+        }
+        // 不定义 `infini`，由 lib.rs 报告编译错误
+        Err(e) => {
+            for line in e.to_string().lines() {
+                println!("cargo:warning={line}")
+            }
+        }
+    }
+
+    // 每个后端一个 cfg，例如 infini_nvidia，参见 search_infini_core::Installation::backends
+    for backend in BACKENDS {
+        let cfg = Cfg::new(format!("infini_{backend}"));
+        if backends.contains(&backend) {
+            cfg.define()
+        }
+    }
+    // 依赖此 crate 的构建脚本从 DEP_INFINI_BACKENDS 读取同一份后端列表
+    println!("cargo:backends={}", backends.join(","));
+
+    // 优先从头文件生成绑定，不可用时退回到仓库中手工维护的绑定
+    // 运行时加载的函数表按手工维护的绑定编写，始终使用它
+    #[cfg(feature = "bindgen")]
+    if let Some(infini) = found.as_ref().ok().filter(|_| !dlopen) {
+        // 找不到 libclang 时 bindgen 会 panic，同样退回到手工维护的绑定
+        match std::panic::catch_unwind(|| generate(&infini.include_dir, ccl)) {
+            Ok(Ok(bindings)) => {
+                // Write the bindings to the $OUT_DIR/bindings.rs file.
+                bindings
+                    .write_to_file(out_path.join("bindings.rs"))
+                    .expect("Couldn't write bindings!");
+                return;
+            }
+            Ok(Err(e)) => {
+                println!("cargo:warning=Unable to generate bindings ({e}), using bundled bindings")
+            }
+            Err(_) => println!("cargo:warning=libclang unavailable, using bundled bindings"),
+        }
+    }
+
+    fs::copy("bundled/bindings.rs", out_path.join("bindings.rs"))
+        .expect("Couldn't copy bundled bindings!");
+}
+
+#[cfg(feature = "bindgen")]
+fn generate(
+    include: &std::path::Path,
+    ccl: bool,
+) -> Result<bindgen::Bindings, bindgen::BindgenError> {
     // The bindgen::Builder is the main entry point to bindgen,
     // and lets you build up options for the resulting bindings.
-    let bindings = bindgen::Builder::default()
+    let mut builder = bindgen::Builder::default()
         // The input header we would like to generate bindings for.
         .header("wrapper.h")
-        .clang_arg(format!("-I{}", include.display()))
+        .clang_arg(format!("-I{}", include.display()));
+    // wrapper.h 只在定义 INFINI_CCL 时包含 infiniccl.h
+    if ccl {
+        builder = builder.clang_arg("-DINFINI_CCL");
+    }
+    builder
         // Only generate bindings for the functions in these namespaces.
         .allowlist_item("infini.*")
         // Annotate the given type with the #[must_use] attribute.
@@ -54,12 +165,4 @@ fn main() {
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         // Finish the builder and generate the bindings.
         .generate()
-        // Unwrap the Result and panic on failure.
-        .expect("Unable to generate bindings");
-
-    // Write the bindings to the $OUT_DIR/bindings.rs file.
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
 }
@@ -0,0 +1,915 @@
+// 手工维护的绑定，不是由 bindgen 生成的。
+//
+// 构建时找不到 InfiniCore 头文件或 libclang、关闭 `bindgen` feature 以及 `dlopen` 模式下使用这个文件。
+// 它按 `infinirt.h`、`infiniop.h`（包括 `infiniop/ops/*.h`）和 `infiniccl.h` 中的声明逐个手写，
+// 只包含本 crate 用到的类型和函数，与 src/ops 中的封装和 src/dl.rs 的函数表一一对应；
+// 排版与 rust-bindgen 0.71.1 的输出相同，以便与构建时生成的绑定互换。
+//
+// 这些声明还没有固定到某个 InfiniCore 版本，与实际链接的库可能存在差异。
+// 用 `bundled/regenerate.sh <tag 或提交>` 从固定版本的头文件重新生成这个文件，
+// 生成的文件头记录所用的版本；CI 按这条记录重新生成并比较，在记录版本之前这项检查失败。
+
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infiniStatus_t {
+    INFINI_STATUS_SUCCESS = 0,
+    INFINI_STATUS_INTERNAL_ERROR = 1,
+    INFINI_STATUS_NOT_IMPLEMENTED = 2,
+    INFINI_STATUS_BAD_PARAM = 3,
+    INFINI_STATUS_NULL_POINTER = 4,
+    INFINI_STATUS_DEVICE_TYPE_NOT_SUPPORTED = 5,
+    INFINI_STATUS_DEVICE_NOT_FOUND = 6,
+    INFINI_STATUS_DEVICE_NOT_INITIALIZED = 7,
+    INFINI_STATUS_DEVICE_ARCHITECTURE_NOT_SUPPORTED = 8,
+    INFINI_STATUS_BAD_TENSOR_DTYPE = 10,
+    INFINI_STATUS_BAD_TENSOR_SHAPE = 11,
+    INFINI_STATUS_BAD_TENSOR_STRIDES = 12,
+    INFINI_STATUS_INSUFFICIENT_WORKSPACE = 13,
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infiniDevice_t {
+    INFINI_DEVICE_CPU = 0,
+    INFINI_DEVICE_NVIDIA = 1,
+    INFINI_DEVICE_CAMBRICON = 2,
+    INFINI_DEVICE_ASCEND = 3,
+    INFINI_DEVICE_METAX = 4,
+    INFINI_DEVICE_MOORE = 5,
+    INFINI_DEVICE_ILUVATAR = 6,
+    INFINI_DEVICE_KUNLUN = 7,
+    INFINI_DEVICE_SUGON = 8,
+    INFINI_DEVICE_TYPE_COUNT = 9,
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infiniDtype_t {
+    INFINI_DTYPE_INVALID = 0,
+    INFINI_DTYPE_BYTE = 1,
+    INFINI_DTYPE_BOOL = 2,
+    INFINI_DTYPE_I8 = 3,
+    INFINI_DTYPE_I16 = 4,
+    INFINI_DTYPE_I32 = 5,
+    INFINI_DTYPE_I64 = 6,
+    INFINI_DTYPE_U8 = 7,
+    INFINI_DTYPE_U16 = 8,
+    INFINI_DTYPE_U32 = 9,
+    INFINI_DTYPE_U64 = 10,
+    INFINI_DTYPE_F8 = 11,
+    INFINI_DTYPE_F16 = 12,
+    INFINI_DTYPE_F32 = 13,
+    INFINI_DTYPE_F64 = 14,
+    INFINI_DTYPE_C16 = 15,
+    INFINI_DTYPE_C32 = 16,
+    INFINI_DTYPE_C64 = 17,
+    INFINI_DTYPE_C128 = 18,
+    INFINI_DTYPE_BF16 = 19,
+}
+pub type infinirtStream_t = *mut ::core::ffi::c_void;
+pub type infinirtEvent_t = *mut ::core::ffi::c_void;
+unsafe extern "C" {
+    pub fn infinirtInit() -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtGetAllDeviceCount(count_array: *mut ::core::ffi::c_int) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtGetDeviceCount(
+        device: infiniDevice_t,
+        count: *mut ::core::ffi::c_int,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtSetDevice(device: infiniDevice_t, device_id: ::core::ffi::c_int)
+    -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtGetDevice(
+        device_ptr: *mut infiniDevice_t,
+        device_id_ptr: *mut ::core::ffi::c_int,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtDeviceSynchronize() -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtStreamCreate(stream_ptr: *mut infinirtStream_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtStreamDestroy(stream: infinirtStream_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtStreamSynchronize(stream: infinirtStream_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtStreamWaitEvent(stream: infinirtStream_t, event: infinirtEvent_t)
+    -> infiniStatus_t;
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infinirtEventStatus_t {
+    INFINIRT_EVENT_COMPLETE = 0,
+    INFINIRT_EVENT_NOT_READY = 1,
+}
+unsafe extern "C" {
+    pub fn infinirtEventCreate(event_ptr: *mut infinirtEvent_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtEventRecord(event: infinirtEvent_t, stream: infinirtStream_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtEventQuery(
+        event: infinirtEvent_t,
+        status_ptr: *mut infinirtEventStatus_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtEventSynchronize(event: infinirtEvent_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtEventDestroy(event: infinirtEvent_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtMalloc(p_ptr: *mut *mut ::core::ffi::c_void, size: usize) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtMallocHost(p_ptr: *mut *mut ::core::ffi::c_void, size: usize) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtFree(ptr: *mut ::core::ffi::c_void) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtFreeHost(ptr: *mut ::core::ffi::c_void) -> infiniStatus_t;
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infinirtMemcpyKind_t {
+    INFINIRT_MEMCPY_H2H = 0,
+    INFINIRT_MEMCPY_H2D = 1,
+    INFINIRT_MEMCPY_D2H = 2,
+    INFINIRT_MEMCPY_D2D = 3,
+}
+unsafe extern "C" {
+    pub fn infinirtMemcpy(
+        dst: *mut ::core::ffi::c_void,
+        src: *const ::core::ffi::c_void,
+        size: usize,
+        kind: infinirtMemcpyKind_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtMemcpyAsync(
+        dst: *mut ::core::ffi::c_void,
+        src: *const ::core::ffi::c_void,
+        size: usize,
+        kind: infinirtMemcpyKind_t,
+        stream: infinirtStream_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtMallocAsync(
+        p_ptr: *mut *mut ::core::ffi::c_void,
+        size: usize,
+        stream: infinirtStream_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinirtFreeAsync(ptr: *mut ::core::ffi::c_void, stream: infinirtStream_t)
+    -> infiniStatus_t;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct InfiniopHandle {
+    _unused: [u8; 0],
+}
+pub type infiniopHandle_t = *mut InfiniopHandle;
+unsafe extern "C" {
+    pub fn infiniopCreateHandle(handle_ptr: *mut infiniopHandle_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyHandle(handle: infiniopHandle_t) -> infiniStatus_t;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct InfiniopTensorDescriptor {
+    _unused: [u8; 0],
+}
+pub type infiniopTensorDescriptor_t = *mut InfiniopTensorDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateTensorDescriptor(
+        desc_ptr: *mut infiniopTensorDescriptor_t,
+        ndim: usize,
+        shape: *const usize,
+        strides: *const isize,
+        dtype: infiniDtype_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyTensorDescriptor(desc: infiniopTensorDescriptor_t) -> infiniStatus_t;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct InfiniopDescriptor {
+    _unused: [u8; 0],
+}
+pub type infiniopOperatorDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopGetDescriptorDeviceType(
+        desc: infiniopOperatorDescriptor_t,
+        device_type: *mut infiniDevice_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopGemmDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateGemmDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopGemmDescriptor_t,
+        c_desc: infiniopTensorDescriptor_t,
+        a_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetGemmWorkspaceSize(
+        desc: infiniopGemmDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGemm(
+        desc: infiniopGemmDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        c: *mut ::core::ffi::c_void,
+        a: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        alpha: f32,
+        beta: f32,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyGemmDescriptor(
+        desc: infiniopGemmDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopAddDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateAddDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopAddDescriptor_t,
+        c_desc: infiniopTensorDescriptor_t,
+        a_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetAddWorkspaceSize(
+        desc: infiniopAddDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopAdd(
+        desc: infiniopAddDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        c: *mut ::core::ffi::c_void,
+        a: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyAddDescriptor(
+        desc: infiniopAddDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopSubDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateSubDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopSubDescriptor_t,
+        c_desc: infiniopTensorDescriptor_t,
+        a_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetSubWorkspaceSize(
+        desc: infiniopSubDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopSub(
+        desc: infiniopSubDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        c: *mut ::core::ffi::c_void,
+        a: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroySubDescriptor(
+        desc: infiniopSubDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopMulDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateMulDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopMulDescriptor_t,
+        c_desc: infiniopTensorDescriptor_t,
+        a_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetMulWorkspaceSize(
+        desc: infiniopMulDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopMul(
+        desc: infiniopMulDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        c: *mut ::core::ffi::c_void,
+        a: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyMulDescriptor(
+        desc: infiniopMulDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopSwiGLUDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateSwiGLUDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopSwiGLUDescriptor_t,
+        c_desc: infiniopTensorDescriptor_t,
+        a_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetSwiGLUWorkspaceSize(
+        desc: infiniopSwiGLUDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopSwiGLU(
+        desc: infiniopSwiGLUDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        c: *mut ::core::ffi::c_void,
+        a: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroySwiGLUDescriptor(
+        desc: infiniopSwiGLUDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopReluDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateReluDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopReluDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetReluWorkspaceSize(
+        desc: infiniopReluDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopRelu(
+        desc: infiniopReluDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyReluDescriptor(
+        desc: infiniopReluDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopGeluDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateGeluDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopGeluDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetGeluWorkspaceSize(
+        desc: infiniopGeluDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGelu(
+        desc: infiniopGeluDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyGeluDescriptor(
+        desc: infiniopGeluDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopSiluDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateSiluDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopSiluDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetSiluWorkspaceSize(
+        desc: infiniopSiluDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopSilu(
+        desc: infiniopSiluDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroySiluDescriptor(
+        desc: infiniopSiluDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopClipDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateClipDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopClipDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        min_val_desc: infiniopTensorDescriptor_t,
+        max_val_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetClipWorkspaceSize(
+        desc: infiniopClipDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopClip(
+        desc: infiniopClipDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        min_val: *const ::core::ffi::c_void,
+        max_val: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyClipDescriptor(
+        desc: infiniopClipDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopRMSNormDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateRMSNormDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopRMSNormDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        w_desc: infiniopTensorDescriptor_t,
+        epsilon: f32,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetRMSNormWorkspaceSize(
+        desc: infiniopRMSNormDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopRMSNorm(
+        desc: infiniopRMSNormDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        w: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyRMSNormDescriptor(
+        desc: infiniopRMSNormDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopLayerNormDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateLayerNormDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopLayerNormDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        w_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+        epsilon: f32,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetLayerNormWorkspaceSize(
+        desc: infiniopLayerNormDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopLayerNorm(
+        desc: infiniopLayerNormDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        w: *const ::core::ffi::c_void,
+        b: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyLayerNormDescriptor(
+        desc: infiniopLayerNormDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopRoPEDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateRoPEDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopRoPEDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        pos_ids: infiniopTensorDescriptor_t,
+        sin_table: infiniopTensorDescriptor_t,
+        cos_table: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetRoPEWorkspaceSize(
+        desc: infiniopRoPEDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopRoPE(
+        desc: infiniopRoPEDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        pos_ids: *const ::core::ffi::c_void,
+        sin_table: *const ::core::ffi::c_void,
+        cos_table: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyRoPEDescriptor(
+        desc: infiniopRoPEDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopCausalSoftmaxDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateCausalSoftmaxDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopCausalSoftmaxDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetCausalSoftmaxWorkspaceSize(
+        desc: infiniopCausalSoftmaxDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopCausalSoftmax(
+        desc: infiniopCausalSoftmaxDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyCausalSoftmaxDescriptor(
+        desc: infiniopCausalSoftmaxDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopAttentionDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateAttentionDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopAttentionDescriptor_t,
+        out_desc: infiniopTensorDescriptor_t,
+        q_desc: infiniopTensorDescriptor_t,
+        k_desc: infiniopTensorDescriptor_t,
+        v_desc: infiniopTensorDescriptor_t,
+        k_cache_desc: infiniopTensorDescriptor_t,
+        v_cache_desc: infiniopTensorDescriptor_t,
+        pos: usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetAttentionWorkspaceSize(
+        desc: infiniopAttentionDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopAttention(
+        desc: infiniopAttentionDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        out: *mut ::core::ffi::c_void,
+        q: *const ::core::ffi::c_void,
+        k: *const ::core::ffi::c_void,
+        v: *const ::core::ffi::c_void,
+        k_cache: *mut ::core::ffi::c_void,
+        v_cache: *mut ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyAttentionDescriptor(
+        desc: infiniopAttentionDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopRandomSampleDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateRandomSampleDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopRandomSampleDescriptor_t,
+        result: infiniopTensorDescriptor_t,
+        probs: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetRandomSampleWorkspaceSize(
+        desc: infiniopRandomSampleDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopRandomSample(
+        desc: infiniopRandomSampleDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        result: *mut ::core::ffi::c_void,
+        probs: *const ::core::ffi::c_void,
+        random_val: f32,
+        topp: f32,
+        topk: ::core::ffi::c_int,
+        temperature: f32,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyRandomSampleDescriptor(
+        desc: infiniopRandomSampleDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopReduceMaxDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateReduceMaxDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopReduceMaxDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        dim: usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetReduceMaxWorkspaceSize(
+        desc: infiniopReduceMaxDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopReduceMax(
+        desc: infiniopReduceMaxDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyReduceMaxDescriptor(
+        desc: infiniopReduceMaxDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopRearrangeDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateRearrangeDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopRearrangeDescriptor_t,
+        dst: infiniopTensorDescriptor_t,
+        src: infiniopTensorDescriptor_t,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopRearrange(
+        desc: infiniopRearrangeDescriptor_t,
+        dst: *mut ::core::ffi::c_void,
+        src: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyRearrangeDescriptor(
+        desc: infiniopRearrangeDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopConvDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateConvDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopConvDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        w_desc: infiniopTensorDescriptor_t,
+        b_desc: infiniopTensorDescriptor_t,
+        pads: *mut ::core::ffi::c_void,
+        strides: *mut ::core::ffi::c_void,
+        dilations: *mut ::core::ffi::c_void,
+        n: usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetConvWorkspaceSize(
+        desc: infiniopConvDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopConv(
+        desc: infiniopConvDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        w: *const ::core::ffi::c_void,
+        bias: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyConvDescriptor(
+        desc: infiniopConvDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopAvgPoolDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateAvgPoolDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopAvgPoolDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        kernel_shape: *const usize,
+        pads: *const usize,
+        strides: *const isize,
+        n: usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetAvgPoolWorkspaceSize(
+        desc: infiniopAvgPoolDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopAvgPool(
+        desc: infiniopAvgPoolDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyAvgPoolDescriptor(
+        desc: infiniopAvgPoolDescriptor_t,
+    ) -> infiniStatus_t;
+}
+pub type infiniopMaxPoolDescriptor_t = *mut InfiniopDescriptor;
+unsafe extern "C" {
+    pub fn infiniopCreateMaxPoolDescriptor(
+        handle: infiniopHandle_t,
+        desc_ptr: *mut infiniopMaxPoolDescriptor_t,
+        y_desc: infiniopTensorDescriptor_t,
+        x_desc: infiniopTensorDescriptor_t,
+        kernel_shape: *const usize,
+        pads: *const usize,
+        strides: *const isize,
+        n: usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopGetMaxPoolWorkspaceSize(
+        desc: infiniopMaxPoolDescriptor_t,
+        size: *mut usize,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopMaxPool(
+        desc: infiniopMaxPoolDescriptor_t,
+        workspace: *mut ::core::ffi::c_void,
+        workspace_size: usize,
+        y: *mut ::core::ffi::c_void,
+        x: *const ::core::ffi::c_void,
+        stream: *mut ::core::ffi::c_void,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infiniopDestroyMaxPoolDescriptor(
+        desc: infiniopMaxPoolDescriptor_t,
+    ) -> infiniStatus_t;
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum infinicclReduceOp_t {
+    INFINICCL_SUM = 0,
+    INFINICCL_PROD = 1,
+    INFINICCL_MAX = 2,
+    INFINICCL_MIN = 3,
+    INFINICCL_AVG = 4,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct InfinicclComm {
+    _unused: [u8; 0],
+}
+pub type infinicclComm_t = *mut InfinicclComm;
+unsafe extern "C" {
+    pub fn infinicclCommInitAll(
+        device_type: infiniDevice_t,
+        comms: *mut infinicclComm_t,
+        ndevice: ::core::ffi::c_int,
+        device_ids: *const ::core::ffi::c_int,
+    ) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinicclCommDestroy(comm: infinicclComm_t) -> infiniStatus_t;
+}
+unsafe extern "C" {
+    pub fn infinicclAllReduce(
+        sendbuf: *mut ::core::ffi::c_void,
+        recvbuf: *mut ::core::ffi::c_void,
+        count: usize,
+        datatype: infiniDtype_t,
+        op: infinicclReduceOp_t,
+        comm: infinicclComm_t,
+        stream: infinirtStream_t,
+    ) -> infiniStatus_t;
+}
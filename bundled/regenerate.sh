@@ -0,0 +1,37 @@
+#!/bin/sh
+# 从固定版本的 InfiniCore 头文件重新生成 bundled/bindings.rs。
+#
+# 用法：bundled/regenerate.sh <InfiniCore 的 tag 或提交>
+#
+# 按给定的 tag 或提交获取 InfiniCore 源码（设置 INFINICORE_SRC 时使用这个已有的克隆），
+# 以与 build.rs 相同的选项运行 bindgen，并在文件头记录所用的版本，CI 按这条记录重新生成并比较。
+# 需要 git、libclang 和 bindgen-cli 0.71.1（cargo install bindgen-cli --version 0.71.1）。
+set -eu
+
+ref=${1:?usage: bundled/regenerate.sh <InfiniCore tag or commit>}
+root=$(cd "$(dirname "$0")/.." && pwd)
+src=${INFINICORE_SRC:-}
+if [ -z "$src" ]; then
+    src=$(mktemp -d)
+    trap 'rm -rf "$src"' EXIT
+    git -C "$src" init -q
+    git -C "$src" fetch -q --depth 1 https://github.com/InfiniTensor/InfiniCore.git "$ref"
+    git -C "$src" checkout -q FETCH_HEAD
+else
+    git -C "$src" checkout -q "$ref"
+fi
+commit=$(git -C "$src" rev-parse HEAD)
+
+out="$root/bundled/bindings.rs"
+{
+    echo "// 由 bundled/regenerate.sh 从 InfiniCore 头文件生成，不要手工修改。"
+    echo "//"
+    echo "// InfiniCore: $ref ($commit)"
+    echo
+    bindgen "$root/wrapper.h" \
+        --allowlist-item 'infini.*' \
+        --default-enum-style rust_non_exhaustive \
+        --use-core \
+        -- -I"$src/include" -DINFINI_CCL
+} >"$out.tmp"
+mv "$out.tmp" "$out"
@@ -1,43 +1,609 @@
 #![deny(warnings)]
 
 use std::{
-    env::{var, var_os},
-    path::PathBuf,
+    env::{split_paths, var, var_os},
+    fmt,
+    fs::{File, read_to_string},
+    io::{self, Read},
+    path::{Path, PathBuf},
 };
 
+/// 找到的 InfiniCore 安装。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Installation {
+    /// 这次安装是从哪里找到的，例如 `INFINI_ROOT` 或 `pkg-config (infinirt.pc)`。
+    pub source: String,
+    /// 头文件目录。
+    pub include_dir: PathBuf,
+    /// 库目录。
+    pub lib_dir: PathBuf,
+    /// 在库目录中找到的库文件。
+    pub libraries: Vec<PathBuf>,
+    /// 检测到的版本，来自 pkg-config 或带版本号的动态库文件名。
+    pub version: Option<String>,
+    /// pkg-config 文件中 `backends` 变量声明的后端列表（逗号分隔）。
+    pub declared_backends: Option<String>,
+}
+
+/// InfiniCore 支持的后端名，与 `infiniDevice_t` 中的设备类型对应。
+pub const BACKENDS: [&str; 9] = [
+    "cpu",
+    "nvidia",
+    "cambricon",
+    "ascend",
+    "metax",
+    "moore",
+    "iluvatar",
+    "kunlun",
+    "sugon",
+];
+
+/// 每个后端在库文件中留下的特征字符串：后端命名空间的 C++ 修饰名前缀，以及依赖的厂商运行时库名。
+const BACKEND_PROBES: [(&str, &[&str]); 8] = [
+    ("cpu", &["8infinirt3cpu"]),
+    ("nvidia", &["8infinirt4cuda", "libcudart.so"]),
+    ("cambricon", &["8infinirt4bang", "libcnrt.so"]),
+    ("ascend", &["8infinirt6ascend", "libascendcl.so"]),
+    (
+        "metax",
+        &["8infinirt4maca", "libhcruntime.so", "libmcruntime.so"],
+    ),
+    ("moore", &["8infinirt4musa", "libmusart.so"]),
+    ("kunlun", &["8infinirt6kunlun", "libxpurt.so"]),
+    ("sugon", &["libgalaxyhip.so", "libamdhip64.so"]),
+];
+
+impl Installation {
+    /// 探测这个安装编译了哪些后端，返回 [`BACKENDS`] 中的名字。
+    ///
+    /// 依次使用：
+    ///
+    /// 1. `INFINI_BACKENDS`（逗号分隔的后端名）；
+    /// 2. 通过 pkg-config 找到时，`.pc` 文件中的 `backends` 变量，例如 `backends=cpu,nvidia`；
+    /// 3. 在找到的库文件中查找各后端的特征字符串。库文件可能有数百 MB，按块读取，不整个读入内存。
+    ///
+    /// 天数智芯与英伟达共用 CUDA 实现，无法从库文件区分，只能通过前两种方式指定。
+    pub fn backends(&self) -> Vec<&'static str> {
+        if let Some(list) = find_env("INFINI_BACKENDS") {
+            return parse_backends(&list);
+        }
+        if let Some(list) = &self.declared_backends {
+            return parse_backends(list);
+        }
+        let probes = BACKEND_PROBES
+            .iter()
+            .flat_map(|(_, probes)| probes.iter().copied())
+            .collect::<Vec<_>>();
+        let mut found = vec![false; probes.len()];
+        for path in &self.libraries {
+            if let Ok(hits) = scan(path, &probes, SCAN_CHUNK) {
+                found.iter_mut().zip(hits).for_each(|(f, hit)| *f |= hit)
+            }
+        }
+        let mut found = found.into_iter();
+        BACKEND_PROBES
+            .into_iter()
+            .filter(|(_, probes)| found.by_ref().take(probes.len()).fold(false, |a, b| a | b))
+            .map(|(backend, _)| backend)
+            .collect()
+    }
+
+    /// 安装根目录，即头文件目录和库目录共同的上级目录。分开指定时可能不存在。
+    pub fn root(&self) -> Option<PathBuf> {
+        let root = self.include_dir.parent()?;
+        if self.lib_dir.parent() == Some(root) {
+            Some(root.to_path_buf())
+        } else {
+            None
+        }
+    }
+}
+
+/// 从逗号分隔的列表中取出 [`BACKENDS`] 中的名字，按 [`BACKENDS`] 的顺序返回。
+fn parse_backends(list: &str) -> Vec<&'static str> {
+    let list = list.split(',').map(str::trim).collect::<Vec<_>>();
+    BACKENDS.into_iter().filter(|b| list.contains(b)).collect()
+}
+
+/// [`scan`] 每次读取的字节数。
+const SCAN_CHUNK: usize = 1 << 20;
+
+/// 按 `chunk` 字节一块读取 `path`，返回每个 `probes` 是否出现在文件中。
+///
+/// 相邻两块之间保留最长特征字符串减一个字节，跨块的特征字符串也能找到。
+fn scan(path: &Path, probes: &[&str], chunk: usize) -> io::Result<Vec<bool>> {
+    let keep = probes.iter().map(|p| p.len()).max().unwrap_or(1) - 1;
+    let mut found = vec![false; probes.len()];
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; keep + chunk];
+    let mut len = 0;
+    while !found.iter().all(|&f| f) {
+        let n = file.read(&mut buf[len..])?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+        let window = &buf[..len];
+        for (f, probe) in found.iter_mut().zip(probes) {
+            *f = *f || window.windows(probe.len()).any(|w| w == probe.as_bytes())
+        }
+        let tail = len.saturating_sub(keep);
+        buf.copy_within(tail..len, 0);
+        len -= tail;
+    }
+    Ok(found)
+}
+
+/// 没有找到 InfiniCore，记录了查找过的每个位置以及被排除的原因。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NotFound {
+    /// 查找的头文件。
+    pub headers: Vec<String>,
+    /// 查找的库。
+    pub libs: Vec<String>,
+    /// 每个候选位置的说明。
+    pub searched: Vec<String>,
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "could not find headers {:?} and libraries {:?}; searched:",
+            self.headers, self.libs,
+        )?;
+        for line in &self.searched {
+            write!(f, "\n  - {line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NotFound {}
+
 pub fn find_infini_rt() -> Option<PathBuf> {
-    find_header_lib("infinirt.h", "infinirt")
+    search(&["infinirt.h"], &["infinirt"]).ok()?.root()
 }
 
 pub fn find_infini_op() -> Option<PathBuf> {
-    find_header_lib("infiniop.h", "infiniop")
+    search(&["infiniop.h"], &["infiniop"]).ok()?.root()
 }
 
 pub fn find_infini_core() -> Option<PathBuf> {
-    find_header_lib("infinicore.h", "infinicore")
+    search(&["infinicore.h"], &["infinicore"]).ok()?.root()
 }
 
-fn find_header_lib(header: &str, lib: &str) -> Option<PathBuf> {
-    const HOME: &str = if cfg!(windows) { "HOMEPATH" } else { "HOME" };
-    let root = match find_env("INFINI_ROOT") {
-        Some(path) => PathBuf::from(path),
-        None => var_os(HOME).map(PathBuf::from).unwrap().join(".infini"),
+/// 查找同时提供 `headers` 和 `libs` 的 InfiniCore 安装。
+///
+/// 依次尝试：
+///
+/// 1. `INFINI_INCLUDE_DIR` 和 `INFINI_LIB_DIR`；
+/// 2. `INFINI_ROOT`；
+/// 3. `PKG_CONFIG_PATH` 和系统 pkg-config 目录中与库同名的 `.pc` 文件；
+/// 4. `~/.infini`；
+/// 5. 动态库搜索路径（`LD_LIBRARY_PATH`、`DYLD_LIBRARY_PATH` 或 `PATH`），头文件取其旁边的 `include`；
+/// 6. 标准前缀 `/usr/local`、`/usr` 和 `/opt/infini`。
+///
+/// 第一个满足条件的位置被采用；全部失败时返回的 [`NotFound`] 说明了每个位置被排除的原因。
+pub fn search(headers: &[&str], libs: &[&str]) -> Result<Installation, NotFound> {
+    let mut search = Search {
+        headers,
+        libs,
+        searched: Vec::new(),
     };
+    match search.run() {
+        Some(found) => Ok(found),
+        None => Err(NotFound {
+            headers: headers.iter().map(|s| s.to_string()).collect(),
+            libs: libs.iter().map(|s| s.to_string()).collect(),
+            searched: search.searched,
+        }),
+    }
+}
+
+struct Search<'a> {
+    headers: &'a [&'a str],
+    libs: &'a [&'a str],
+    searched: Vec<String>,
+}
+
+impl Search<'_> {
+    fn run(&mut self) -> Option<Installation> {
+        match (find_env("INFINI_INCLUDE_DIR"), find_env("INFINI_LIB_DIR")) {
+            (Some(include), Some(lib)) => {
+                let source = "INFINI_INCLUDE_DIR/INFINI_LIB_DIR".into();
+                if let Some(found) = self.probe(source, include.into(), lib.into(), None, None) {
+                    return Some(found);
+                }
+            }
+            (None, None) => {}
+            (Some(_), None) => self
+                .searched
+                .push("INFINI_INCLUDE_DIR: ignored because INFINI_LIB_DIR is not set".into()),
+            (None, Some(_)) => self
+                .searched
+                .push("INFINI_LIB_DIR: ignored because INFINI_INCLUDE_DIR is not set".into()),
+        }
+
+        match find_env("INFINI_ROOT") {
+            Some(root) => {
+                if let Some(found) = self.prefix("INFINI_ROOT", root.into()) {
+                    return Some(found);
+                }
+            }
+            None => self.searched.push("INFINI_ROOT: not set".into()),
+        }
+
+        for lib in self.libs {
+            match pkg_config(lib) {
+                Ok(pc) => {
+                    let source = format!("pkg-config ({})", pc.file.display());
+                    if let Some(found) =
+                        self.probe(source, pc.include_dir, pc.lib_dir, pc.version, pc.backends)
+                    {
+                        return Some(found);
+                    }
+                }
+                Err(reason) => self
+                    .searched
+                    .push(format!("pkg-config ({lib}.pc): {reason}")),
+            }
+        }
+
+        const HOME: &str = if cfg!(windows) { "HOMEPATH" } else { "HOME" };
+        match var_os(HOME) {
+            Some(home) => {
+                if let Some(found) = self.prefix("~/.infini", PathBuf::from(home).join(".infini")) {
+                    return Some(found);
+                }
+            }
+            None => self.searched.push(format!("~/.infini: {HOME} is not set")),
+        }
+
+        const LIBRARY_PATH: &str = if cfg!(windows) {
+            "PATH"
+        } else if cfg!(target_os = "macos") {
+            "DYLD_LIBRARY_PATH"
+        } else {
+            "LD_LIBRARY_PATH"
+        };
+        println!("cargo:rerun-if-env-changed={LIBRARY_PATH}");
+        for dir in var_os(LIBRARY_PATH).iter().flat_map(split_paths) {
+            let Some(include_dir) = dir.parent().map(|prefix| prefix.join("include")) else {
+                continue;
+            };
+            let source = format!("{LIBRARY_PATH} entry {}", dir.display());
+            if let Some(found) = self.probe(source, include_dir, dir, None, None) {
+                return Some(found);
+            }
+        }
+
+        if !cfg!(windows) {
+            for prefix in ["/usr/local", "/usr", "/opt/infini"] {
+                if let Some(found) = self.prefix("standard prefix", prefix.into()) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
 
-    if !root.join("include").join(header).exists() {
-        return None;
+    fn prefix(&mut self, source: &str, root: PathBuf) -> Option<Installation> {
+        let source = format!("{source} ({})", root.display());
+        self.probe(source, root.join("include"), root.join("lib"), None, None)
     }
-    let mut contains = false;
-    for res in root.join("lib").read_dir().ok()? {
-        if res.ok()?.file_name().to_str()?.contains(lib) {
-            contains = true;
+
+    fn probe(
+        &mut self,
+        source: String,
+        include_dir: PathBuf,
+        lib_dir: PathBuf,
+        version: Option<String>,
+        declared_backends: Option<String>,
+    ) -> Option<Installation> {
+        match check(self.headers, self.libs, &include_dir, &lib_dir) {
+            Ok(libraries) => Some(Installation {
+                version: version.or_else(|| so_version(&libraries)),
+                source,
+                include_dir,
+                lib_dir,
+                libraries,
+                declared_backends,
+            }),
+            Err(reason) => {
+                self.searched.push(format!("{source}: {reason}"));
+                None
+            }
+        }
+    }
+}
+
+/// 检查头文件和库是否都存在，返回找到的库文件。
+fn check(
+    headers: &[&str],
+    libs: &[&str],
+    include_dir: &Path,
+    lib_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    for header in headers {
+        let path = include_dir.join(header);
+        if !path.is_file() {
+            return Err(format!("{} not found", path.display()));
+        }
+    }
+    let entries = lib_dir
+        .read_dir()
+        .map_err(|e| format!("cannot read {}: {e}", lib_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    let mut libraries = Vec::new();
+    for lib in libs {
+        let matched = entries
+            .iter()
+            .filter(|path| is_library(path, lib))
+            .cloned()
+            .collect::<Vec<_>>();
+        if matched.is_empty() {
+            return Err(format!("no {lib} library in {}", lib_dir.display()));
+        }
+        libraries.extend(matched)
+    }
+    libraries.sort();
+    Ok(libraries)
+}
+
+/// 判断文件名是否为库 `lib` 的静态库或动态库，例如 `libinfinirt.so.1`、`libinfinirt.a`、`infinirt.dll`。
+fn is_library(path: &Path, lib: &str) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let rest = if cfg!(windows) {
+        name.strip_prefix(lib)
+    } else {
+        name.strip_prefix("lib")
+            .and_then(|name| name.strip_prefix(lib))
+    };
+    match rest {
+        Some(".so" | ".a" | ".dylib" | ".dll" | ".lib") => true,
+        Some(rest) => {
+            rest.starts_with(".so.") || (rest.ends_with(".dylib") && rest.starts_with('.'))
+        }
+        None => false,
+    }
+}
+
+/// 从 `libxxx.so.1.2.3` 这样的文件名中取出版本号。
+fn so_version(libraries: &[PathBuf]) -> Option<String> {
+    libraries
+        .iter()
+        .filter_map(|path| {
+            path.file_name()?
+                .to_str()?
+                .split_once(".so.")
+                .map(|(_, v)| v)
+        })
+        .max_by_key(|v| v.len())
+        .map(str::to_string)
+}
+
+struct PkgConfig {
+    file: PathBuf,
+    include_dir: PathBuf,
+    lib_dir: PathBuf,
+    version: Option<String>,
+    backends: Option<String>,
+}
+
+/// 在 pkg-config 搜索路径中查找并解析 `{lib}.pc`。
+fn pkg_config(lib: &str) -> Result<PkgConfig, String> {
+    let mut dirs = find_env("PKG_CONFIG_PATH")
+        .map(|paths| split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if !cfg!(windows) {
+        dirs.extend(
+            [
+                "/usr/local/lib/pkgconfig",
+                "/usr/local/share/pkgconfig",
+                "/usr/lib/pkgconfig",
+                "/usr/lib64/pkgconfig",
+                "/usr/share/pkgconfig",
+            ]
+            .map(PathBuf::from),
+        );
+        if let Ok(arch) = var("CARGO_CFG_TARGET_ARCH") {
+            dirs.push(format!("/usr/lib/{arch}-linux-gnu/pkgconfig").into())
+        }
+    }
+
+    let name = format!("{lib}.pc");
+    let file = dirs
+        .iter()
+        .map(|dir| dir.join(&name))
+        .find(|file| file.is_file())
+        .ok_or("not found".to_string())?;
+    let text = read_to_string(&file).map_err(|e| format!("cannot read {}: {e}", file.display()))?;
+
+    let (vars, version) = parse_pc(&text);
+    let get = |key: &str| {
+        vars.iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| PathBuf::from(v))
+            .ok_or(format!("{} does not define {key}", file.display()))
+    };
+    Ok(PkgConfig {
+        include_dir: get("includedir")?,
+        lib_dir: get("libdir")?,
+        backends: get("backends")
+            .ok()
+            .map(|v| v.to_string_lossy().into_owned()),
+        version,
+        file,
+    })
+}
+
+/// 解析 `.pc` 文件的内容，返回按出现顺序展开后的变量和 `Version` 字段。
+///
+/// 每行按最先出现的 `:` 或 `=` 分类：`=` 定义变量，`:` 是字段，
+/// 因此 `Cflags: -DFOO=1` 这样值中带 `=` 的字段不会被当作变量。
+fn parse_pc(text: &str) -> (Vec<(String, String)>, Option<String>) {
+    let mut vars = Vec::<(String, String)>::new();
+    let mut version = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some(i) = line.find([':', '=']) else {
+            continue;
+        };
+        let (key, value) = (line[..i].trim(), line[i + 1..].trim());
+        if line.as_bytes()[i] == b'=' {
+            let value = expand(value, &vars);
+            vars.push((key.to_string(), value))
+        } else if key == "Version" {
+            version = Some(expand(value, &vars))
+        }
+    }
+    (vars, version)
+}
+
+/// 展开 pkg-config 值中的 `${var}`。
+fn expand(value: &str, vars: &[(String, String)]) -> String {
+    let mut ans = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
             break;
+        };
+        let key = &rest[start + 2..start + len];
+        ans.push_str(&rest[..start]);
+        if let Some((_, v)) = vars.iter().rev().find(|(k, _)| k == key) {
+            ans.push_str(v)
         }
+        rest = &rest[start + len + 1..];
     }
-    if contains { Some(root) } else { None }
+    ans.push_str(rest);
+    ans
 }
 
 fn find_env(key: &str) -> Option<String> {
     println!("cargo:rerun-if-env-changed={key}");
     var(key).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn library_names() {
+        let lib = |name: &str| is_library(Path::new(name), "infinirt");
+        assert!(lib("/opt/infini/lib/libinfinirt.so"));
+        assert!(lib("libinfinirt.so.1"));
+        assert!(lib("libinfinirt.so.1.2.3"));
+        assert!(lib("libinfinirt.a"));
+        assert!(lib("libinfinirt.dylib"));
+        assert!(lib("libinfinirt.1.dylib"));
+        assert!(!lib("libinfinirt-cuda.so"));
+        assert!(!lib("libinfiniop.so"));
+        assert!(!lib("infinirt.so"));
+        assert!(!lib("libinfinirt.sox"));
+        assert!(!lib("libinfinirt.h"));
+    }
+
+    #[test]
+    fn so_versions() {
+        let paths = |names: &[&str]| names.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(so_version(&paths(&["libinfinirt.so"])), None);
+        assert_eq!(
+            so_version(&paths(&[
+                "/lib/libinfinirt.so",
+                "/lib/libinfinirt.so.1",
+                "/lib/libinfinirt.so.1.2.3",
+            ])),
+            Some("1.2.3".into()),
+        );
+        assert_eq!(so_version(&[]), None);
+    }
+
+    #[test]
+    fn backend_lists() {
+        assert_eq!(parse_backends("nvidia, cpu,unknown"), ["cpu", "nvidia"]);
+        assert_eq!(parse_backends(""), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn scan_chunks() {
+        let path = std::env::temp_dir().join(format!("search-infini-core-{}", std::process::id()));
+        std::fs::write(&path, b"\0\x7fELF..8infinirt4cuda..libcudart.so\0").unwrap();
+        let probes = ["8infinirt4cuda", "libcudart.so", "8infinirt3cpu"];
+        // 块比特征字符串短，特征字符串都跨越了块的边界
+        for chunk in [1, 3, 7, SCAN_CHUNK] {
+            assert_eq!(
+                scan(&path, &probes, chunk).unwrap(),
+                [true, true, false],
+                "chunk {chunk}"
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert!(scan(&path, &probes, SCAN_CHUNK).is_err());
+    }
+
+    #[test]
+    fn expand_vars() {
+        let vars = vec![
+            ("prefix".to_string(), "/opt/infini".to_string()),
+            ("libdir".to_string(), "/opt/infini/lib".to_string()),
+            ("prefix".to_string(), "/usr".to_string()),
+        ];
+        // 重复定义时取最后一个
+        assert_eq!(expand("${prefix}/include", &vars), "/usr/include");
+        assert_eq!(
+            expand("-L${libdir} -L${libdir}64", &vars),
+            "-L/opt/infini/lib -L/opt/infini/lib64"
+        );
+        // 未定义的变量展开为空，未闭合的引用原样保留
+        assert_eq!(expand("${missing}/x", &vars), "/x");
+        assert_eq!(expand("${prefix", &vars), "${prefix");
+        assert_eq!(expand("plain", &vars), "plain");
+    }
+
+    #[test]
+    fn parse_pc_file() {
+        let (vars, version) = parse_pc(
+            "\
+# comment: with = signs
+prefix=/opt/infini
+exec_prefix = ${prefix}
+libdir=${exec_prefix}/lib
+includedir=${prefix}/include
+
+Name: infinirt
+Description: key=value inside a field
+Version: 0.${minor}
+Cflags: -I${includedir} -DINFINI_API=1
+Libs: -L${libdir} -linfinirt
+minor=2
+",
+        );
+        let get = |key: &str| {
+            vars.iter()
+                .rev()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("prefix"), Some("/opt/infini"));
+        assert_eq!(get("exec_prefix"), Some("/opt/infini"));
+        assert_eq!(get("libdir"), Some("/opt/infini/lib"));
+        assert_eq!(get("includedir"), Some("/opt/infini/include"));
+        assert_eq!(get("minor"), Some("2"));
+        // 字段不是变量，即使值中有 `=`
+        assert_eq!(get("Description"), None);
+        assert_eq!(get("Cflags"), None);
+        assert_eq!(vars.len(), 5);
+        // 变量在使用处之后才定义时展开为空
+        assert_eq!(version.as_deref(), Some("0."));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::Tensor;
+use digit_layout::DigitLayout;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// 描述符缓存的统计信息。
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct CacheStats {
+    /// 命中次数。
+    pub hits: u64,
+    /// 未命中次数。
+    pub misses: u64,
+    /// 因超出容量而淘汰的描述符数。
+    pub evictions: u64,
+    /// 当前缓存的描述符数。
+    pub len: usize,
+    /// 容量上限。
+    pub capacity: usize,
+}
+
+/// 缓存键：算子类型、每个张量的数据类型、形状和步长，以及创建描述符时的标量参数。
+///
+/// 缓存属于某个 [`Handle`](crate::Handle)，因此键中隐含了句柄所在的设备。
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    op: TypeId,
+    tensors: Vec<(DigitLayout, Vec<usize>, Vec<isize>)>,
+    args: Vec<u64>,
+}
+
+impl Key {
+    pub fn new<O: Any>(tensors: &[&Tensor], args: &[u64]) -> Self {
+        Self {
+            op: TypeId::of::<O>(),
+            tensors: tensors
+                .iter()
+                .map(|t| (t.dt(), t.shape().to_vec(), t.strides().to_vec()))
+                .collect(),
+            args: args.to_vec(),
+        }
+    }
+}
+
+/// 以最近最少使用策略淘汰的算子描述符缓存。
+///
+/// 查找和插入分为两步，调用者在两步之间创建描述符，创建期间不占用缓存。
+pub(crate) struct OpCache {
+    map: HashMap<Key, (Arc<dyn Any + Send + Sync>, u64)>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl OpCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            map: HashMap::new(),
+            clock: 0,
+            stats: CacheStats {
+                capacity,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.map.len(),
+            ..self.stats
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.stats.capacity = capacity;
+        self.shrink();
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// 查找一个算子并记为最近使用，同时计入命中或未命中。
+    pub fn get<O: Any + Send + Sync>(&mut self, key: &Key) -> Option<Arc<O>> {
+        self.clock += 1;
+        match self.map.get_mut(key) {
+            Some((op, stamp)) => {
+                self.stats.hits += 1;
+                *stamp = self.clock;
+                Some(op.clone().downcast().unwrap())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// 插入新建的算子，缓存满时淘汰最久未使用的项。
+    ///
+    /// 其他调用者可能在创建期间插入了相同的键，此时保留已有的算子并返回它。
+    pub fn insert<O: Any + Send + Sync>(&mut self, key: Key, op: Arc<O>) -> Arc<O> {
+        self.clock += 1;
+        if let Some((existing, stamp)) = self.map.get_mut(&key) {
+            *stamp = self.clock;
+            return existing.clone().downcast().unwrap();
+        }
+        if self.stats.capacity > 0 {
+            self.map.insert(key, (op.clone(), self.clock));
+            self.shrink();
+        }
+        op
+    }
+
+    fn shrink(&mut self) {
+        while self.map.len() > self.stats.capacity {
+            let oldest = self
+                .map
+                .iter()
+                .min_by_key(|(_, (_, stamp))| *stamp)
+                .map(|(key, _)| key.clone())
+                .unwrap();
+            self.map.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, OpCache};
+    use digit_layout::{DigitLayout, types};
+    use std::{any::TypeId, sync::Arc};
+
+    /// 算子 `O` 以 `dt` 类型、长度为 `n` 的一维连续张量和标量参数 `args` 为键。
+    ///
+    /// 直接构造键，不创建 infiniop 张量描述符。
+    fn key_of<O: 'static>(dt: DigitLayout, n: usize, args: &[u64]) -> Key {
+        Key {
+            op: TypeId::of::<O>(),
+            tensors: vec![(dt, vec![n], vec![1])],
+            args: args.to_vec(),
+        }
+    }
+
+    fn key<O: 'static>(n: usize) -> Key {
+        key_of::<O>(types::F32, n, &[])
+    }
+
+    /// 查找，未命中时插入 `n`。
+    fn get_or_insert(cache: &mut OpCache, n: usize) -> Arc<usize> {
+        cache
+            .get(&key::<usize>(n))
+            .unwrap_or_else(|| cache.insert(key::<usize>(n), Arc::new(n)))
+    }
+
+    #[test]
+    fn lru() {
+        let mut cache = OpCache::new(2);
+        get_or_insert(&mut cache, 1);
+        get_or_insert(&mut cache, 2);
+        // 命中 1 后 2 成为最久未使用的项
+        assert_eq!(*get_or_insert(&mut cache, 1), 1);
+        get_or_insert(&mut cache, 3);
+
+        let stats = cache.stats();
+        assert_eq!(
+            (stats.hits, stats.misses, stats.evictions, stats.len),
+            (1, 3, 1, 2)
+        );
+        assert!(cache.get::<usize>(&key::<usize>(1)).is_some());
+        assert!(cache.get::<usize>(&key::<usize>(3)).is_some());
+        assert!(cache.get::<usize>(&key::<usize>(2)).is_none());
+    }
+
+    #[test]
+    fn key_includes_type_and_args() {
+        let mut cache = OpCache::new(4);
+        cache.insert(key_of::<u32>(types::F32, 4, &[1]), Arc::new(1u32));
+        assert!(
+            cache
+                .get::<u32>(&key_of::<u32>(types::F32, 4, &[1]))
+                .is_some()
+        );
+        assert!(
+            cache
+                .get::<u32>(&key_of::<u32>(types::F32, 4, &[2]))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get::<u64>(&key_of::<u64>(types::F32, 4, &[1]))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get::<u32>(&key_of::<u32>(types::F16, 4, &[1]))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn racing_insert_keeps_existing() {
+        let mut cache = OpCache::new(2);
+        let first = cache.insert(key::<usize>(1), Arc::new(1));
+        let second = cache.insert(key::<usize>(1), Arc::new(10));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.stats().len, 1);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut cache = OpCache::new(0);
+        // 容量为 0 时不缓存，但仍然返回新建的值
+        assert_eq!(*get_or_insert(&mut cache, 1), 1);
+        assert_eq!(cache.stats().len, 0);
+
+        cache.set_capacity(3);
+        for n in 0..3 {
+            get_or_insert(&mut cache, n);
+        }
+        cache.set_capacity(1);
+        let stats = cache.stats();
+        assert_eq!((stats.len, stats.evictions, stats.capacity), (1, 2, 1));
+        assert!(cache.get::<usize>(&key::<usize>(2)).is_some());
+
+        cache.clear();
+        assert_eq!(cache.stats().len, 0);
+    }
+}
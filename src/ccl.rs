@@ -0,0 +1,338 @@
+use crate::{
+    AsRaw, DevByte, Device, Error, Stream,
+    bindings::{infiniDtype_t, infinicclComm_t, infinicclReduceOp_t},
+};
+use digit_layout::DigitLayout;
+use std::{ffi::c_int, ptr::null_mut};
+
+/// 全规约的运算。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReduceOp {
+    /// 求和。
+    Sum,
+    /// 求积。
+    Prod,
+    /// 最大值。
+    Max,
+    /// 最小值。
+    Min,
+    /// 平均值。
+    Avg,
+}
+
+impl From<ReduceOp> for infinicclReduceOp_t {
+    fn from(op: ReduceOp) -> Self {
+        match op {
+            ReduceOp::Sum => Self::INFINICCL_SUM,
+            ReduceOp::Prod => Self::INFINICCL_PROD,
+            ReduceOp::Max => Self::INFINICCL_MAX,
+            ReduceOp::Min => Self::INFINICCL_MIN,
+            ReduceOp::Avg => Self::INFINICCL_AVG,
+        }
+    }
+}
+
+/// 通信组中一个设备的 infiniccl 通信器 (`infinicclComm_t`)。
+///
+/// 同一组的通信器由 [`Communicator::init_all`] 一次创建，集合操作需要组内每个通信器都在各自的流上调用。
+pub struct Communicator {
+    raw: infinicclComm_t,
+    device: Device,
+    rank: usize,
+    size: usize,
+}
+
+unsafe impl Send for Communicator {}
+unsafe impl Sync for Communicator {}
+
+impl Drop for Communicator {
+    fn drop(&mut self) {
+        infini!(infinicclCommDestroy(self.raw))
+    }
+}
+
+impl AsRaw for Communicator {
+    type Raw = infinicclComm_t;
+    #[inline]
+    unsafe fn as_raw(&self) -> Self::Raw {
+        self.raw
+    }
+}
+
+impl Communicator {
+    /// 在 `devices` 上创建一个通信组，返回的第 i 个通信器属于 `devices[i]`，其秩为 i。
+    ///
+    /// 所有设备必须是同一类型。
+    pub fn init_all(devices: &[Device]) -> Result<Vec<Self>, Error> {
+        let Some(&first) = devices.first() else {
+            return Ok(Vec::new());
+        };
+        if let Some(&found) = devices.iter().find(|d| d.ty != first.ty) {
+            return Err(Error::DeviceMismatch {
+                expected: first,
+                found,
+            });
+        }
+
+        crate::Runtime::get();
+        let ids = devices.iter().map(|d| d.id as c_int).collect::<Vec<_>>();
+        let mut comms = vec![null_mut(); devices.len()];
+        Error::check(unsafe {
+            crate::bindings::infinicclCommInitAll(
+                first.ty,
+                comms.as_mut_ptr(),
+                devices.len() as _,
+                ids.as_ptr(),
+            )
+        })?;
+        Ok(comms
+            .into_iter()
+            .zip(devices)
+            .enumerate()
+            .map(|(rank, (raw, &device))| Self {
+                raw,
+                device,
+                rank,
+                size: devices.len(),
+            })
+            .collect())
+    }
+
+    /// 通信器所在的设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// 通信器在组内的秩。
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// 组内的通信器数。
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 在 `stream` 上对组内所有 `src` 按 `op` 做全规约，结果写入每个通信器的 `dst`。
+    ///
+    /// `src` 和 `dst` 是同样长度的 `dt` 类型数组，可以指向同一块内存。
+    pub fn all_reduce(
+        &self,
+        dst: &mut [DevByte],
+        src: &[DevByte],
+        dt: DigitLayout,
+        op: ReduceOp,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        if dst.len() != src.len() {
+            return Err(Error::BadShape(format!(
+                "all-reduce dst has {} bytes but src has {}",
+                dst.len(),
+                src.len(),
+            )));
+        }
+        unsafe { self.all_reduce_raw(dst.as_mut_ptr(), src.as_ptr(), dst.len(), dt, op, stream) }
+    }
+
+    /// 在 `stream` 上原地全规约 `buf`。
+    pub fn all_reduce_inplace(
+        &self,
+        buf: &mut [DevByte],
+        dt: DigitLayout,
+        op: ReduceOp,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let ptr = buf.as_mut_ptr();
+        unsafe { self.all_reduce_raw(ptr, ptr, buf.len(), dt, op, stream) }
+    }
+
+    /// 在 `stream` 上把秩为 `root` 的通信器的 `buf` 广播到组内所有通信器的 `buf`，
+    /// `root` 不在组内时返回 [`Error::BadShape`]。
+    ///
+    /// infiniccl 只提供全规约，广播通过其他秩贡献 0 的求和实现，因此：
+    ///
+    /// * 非根秩在 `stream` 上临时分配并清零与 `buf` 等长的内存，传输量与全规约相同，高于原生广播；
+    /// * 浮点数据按值求和，根秩的 `-0.0` 在所有秩上都变为 `+0.0`，NaN 的载荷也可能改变。
+    pub fn broadcast(
+        &self,
+        buf: &mut [DevByte],
+        dt: DigitLayout,
+        root: usize,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        if root >= self.size {
+            return Err(Error::BadShape(format!(
+                "broadcast root {root} out of group of {}",
+                self.size,
+            )));
+        }
+        self.check_stream(stream)?;
+        if self.rank == root {
+            return self.all_reduce_inplace(buf, dt, ReduceOp::Sum, stream);
+        }
+        let zeros = stream.from_host(&vec![0u8; buf.len()]);
+        let ans = self.all_reduce(buf, &zeros, dt, ReduceOp::Sum, stream);
+        stream.free(zeros);
+        ans
+    }
+
+    /// 在 `stream` 上把组内所有通信器的 `src` 按秩顺序拼接到每个通信器的 `dst`。
+    ///
+    /// `dst` 的长度必须是 `src` 的组大小倍。与 [`Communicator::broadcast`] 一样通过求和实现：
+    /// 每个秩在 `stream` 上临时分配与 `dst` 等长的内存，除自己的一段外清零，
+    /// 再对整个 `dst` 做全规约，传输量是原生全收集的组大小倍，`-0.0` 同样变为 `+0.0`。
+    pub fn all_gather(
+        &self,
+        dst: &mut [DevByte],
+        src: &[DevByte],
+        dt: DigitLayout,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let len = src.len();
+        if dst.len() != len * self.size {
+            return Err(Error::BadShape(format!(
+                "all-gather dst has {} bytes, expected {} x {len}",
+                dst.len(),
+                self.size,
+            )));
+        }
+        self.check_stream(stream)?;
+        let mut gathered = stream.from_host(&vec![0u8; dst.len()]);
+        stream.memcpy_d2d(&mut gathered[self.rank * len..][..len], src);
+        let ans = self.all_reduce(dst, &gathered, dt, ReduceOp::Sum, stream);
+        stream.free(gathered);
+        ans
+    }
+
+    /// 检查 `stream` 在通信器所在的设备上。
+    fn check_stream(&self, stream: &Stream) -> Result<(), Error> {
+        if stream.device() != self.device {
+            return Err(Error::DeviceMismatch {
+                expected: self.device,
+                found: stream.device(),
+            });
+        }
+        Ok(())
+    }
+
+    unsafe fn all_reduce_raw(
+        &self,
+        dst: *mut DevByte,
+        src: *const DevByte,
+        nbytes: usize,
+        dt: DigitLayout,
+        op: ReduceOp,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        self.check_stream(stream)?;
+        let dtype = infiniDtype_t::try_from(dt).map_err(|e| Error::BadDtype(e.to_string()))?;
+        if !nbytes.is_multiple_of(dt.nbytes()) {
+            return Err(Error::BadShape(format!(
+                "{nbytes} bytes is not a whole number of {dt}"
+            )));
+        }
+        Error::check(unsafe {
+            crate::bindings::infinicclAllReduce(
+                src.cast_mut().cast(),
+                dst.cast(),
+                nbytes / dt.nbytes(),
+                dtype,
+                op.into(),
+                self.raw,
+                stream.as_raw(),
+            )
+        })
+    }
+}
+
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod tests {
+    use super::{Communicator, ReduceOp};
+    use crate::{DevByte, Device, Error, Stream, available_backends, infiniDevice_t};
+    use digit_layout::types;
+    use std::thread;
+
+    fn read(stream: &Stream, src: &[DevByte]) -> Vec<f32> {
+        let mut host = vec![0f32; src.len() / 4];
+        stream.memcpy_d2h(&mut host, src);
+        stream.synchronize();
+        host
+    }
+
+    /// 在组内每个通信器各自的线程和流上运行 `f`，返回各秩的结果。
+    fn run<T: Send>(
+        comms: &[Communicator],
+        f: impl Fn(&Communicator, &Stream) -> T + Sync,
+    ) -> Vec<T> {
+        thread::scope(|s| {
+            let handles = comms
+                .iter()
+                .map(|comm| s.spawn(|| f(comm, &comm.device().stream())))
+                .collect::<Vec<_>>();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
+
+    #[test]
+    #[ignore = "InfiniCore 的 CPU 后端通常只报告 1 个设备，通信组至少需要 2 个"]
+    fn cpu_group() {
+        let cpus = available_backends()
+            .into_iter()
+            .find(|&(ty, _)| ty == infiniDevice_t::INFINI_DEVICE_CPU)
+            .map_or(0, |(_, n)| n);
+        assert!(cpus >= 2, "{cpus} CPU device(s), a group needs at least 2");
+        let devices = (0..2)
+            .map(|id| Device {
+                ty: infiniDevice_t::INFINI_DEVICE_CPU,
+                id,
+            })
+            .collect::<Vec<_>>();
+        let comms = Communicator::init_all(&devices).unwrap();
+        assert_eq!(
+            comms.iter().map(Communicator::rank).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(comms.iter().all(|c| c.size() == 2));
+
+        let data = |rank: usize| (0..4).map(|i| (rank * 10 + i) as f32).collect::<Vec<_>>();
+
+        let sums = run(&comms, |comm, stream| {
+            let mut buf = stream.from_host(&data(comm.rank()));
+            comm.all_reduce_inplace(&mut buf, types::F32, ReduceOp::Sum, stream)
+                .unwrap();
+            read(stream, &buf)
+        });
+        assert!(sums.iter().all(|s| *s == [10., 12., 14., 16.]));
+
+        let broadcast = run(&comms, |comm, stream| {
+            let mut buf = stream.from_host(&data(comm.rank()));
+            comm.broadcast(&mut buf, types::F32, 1, stream).unwrap();
+            read(stream, &buf)
+        });
+        assert!(broadcast.iter().all(|b| *b == data(1)));
+
+        let gathered = run(&comms, |comm, stream| {
+            let src = stream.from_host(&data(comm.rank()));
+            let mut dst = stream.malloc(src.len() * 2);
+            comm.all_gather(&mut dst, &src, types::F32, stream).unwrap();
+            read(stream, &dst)
+        });
+        let expected = [data(0), data(1)].concat();
+        assert!(gathered.iter().all(|g| *g == expected));
+
+        // 流必须在通信器的设备上，根秩必须在组内
+        let stream = devices[1].stream();
+        let mut buf = stream.malloc(16);
+        assert!(matches!(
+            comms[0].broadcast(&mut buf, types::F32, 0, &stream),
+            Err(Error::DeviceMismatch { .. })
+        ));
+        assert!(matches!(
+            comms[1].broadcast(&mut buf, types::F32, 2, &stream),
+            Err(Error::BadShape(_))
+        ));
+    }
+}
@@ -0,0 +1,453 @@
+//! infinirt 的纯 Rust 参考实现，由 `cpu` feature 启用。
+//!
+//! 这个模块以与 C 库相同的符号名导出 infinirt 函数，`bindings` 中的声明直接链接到这里，
+//! 因此 [`Device`](crate::Device)、[`Stream`](crate::Stream)、[`Event`](crate::Event)、
+//! [`DevBlob`](crate::DevBlob) 和 [`HostBlob`](crate::HostBlob) 无需修改即可在没有
+//! InfiniCore 的机器上运行：
+//!
+//! - 只有一个设备 `INFINI_DEVICE_CPU:0`，“设备”内存就是主机内存；
+//! - 流是同步的，提交到流上的操作立即完成；
+//! - 事件总是处于完成状态。
+//!
+//! infiniop 没有对应的实现，这个 feature 下调用算子会导致链接失败。
+#![allow(non_snake_case)]
+
+use crate::bindings::{
+    infiniDevice_t, infiniStatus_t, infinirtEvent_t, infinirtEventStatus_t, infinirtMemcpyKind_t,
+    infinirtStream_t,
+};
+use std::{cell::Cell, ffi::c_int, ffi::c_void};
+
+use infiniStatus_t::{
+    INFINI_STATUS_BAD_PARAM as BAD_PARAM, INFINI_STATUS_DEVICE_NOT_FOUND as DEVICE_NOT_FOUND,
+    INFINI_STATUS_DEVICE_TYPE_NOT_SUPPORTED as DEVICE_TYPE_NOT_SUPPORTED,
+    INFINI_STATUS_INTERNAL_ERROR as INTERNAL_ERROR, INFINI_STATUS_NULL_POINTER as NULL_POINTER,
+    INFINI_STATUS_SUCCESS as SUCCESS,
+};
+
+thread_local! {
+    /// 当前线程活动的设备 ID。只有 CPU 设备，因此只记录 ID。
+    static CURRENT: Cell<c_int> = const { Cell::new(0) };
+}
+
+/// 流和事件没有状态，只需要一个唯一的非空句柄。
+fn new_object() -> *mut c_void {
+    Box::into_raw(Box::new(0u8)).cast()
+}
+
+unsafe fn drop_object(ptr: *mut c_void) -> infiniStatus_t {
+    if ptr.is_null() {
+        return NULL_POINTER;
+    }
+    drop(unsafe { Box::from_raw(ptr.cast::<u8>()) });
+    SUCCESS
+}
+
+unsafe fn alloc(p_ptr: *mut *mut c_void, size: usize) -> infiniStatus_t {
+    if p_ptr.is_null() {
+        return NULL_POINTER;
+    }
+    // malloc(0) 可能返回空指针，至少分配 1 字节
+    let ptr = unsafe { libc::malloc(size.max(1)) };
+    if ptr.is_null() {
+        return INTERNAL_ERROR;
+    }
+    unsafe { *p_ptr = ptr };
+    #[cfg(test)]
+    tests::LIVE.set(tests::LIVE.get() + 1);
+    SUCCESS
+}
+
+unsafe fn dealloc(ptr: *mut c_void) -> infiniStatus_t {
+    unsafe { libc::free(ptr) };
+    #[cfg(test)]
+    tests::LIVE.set(tests::LIVE.get() - 1);
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtInit() -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtGetAllDeviceCount(count_array: *mut c_int) -> infiniStatus_t {
+    if count_array.is_null() {
+        return NULL_POINTER;
+    }
+    let n = infiniDevice_t::INFINI_DEVICE_TYPE_COUNT as usize;
+    let counts = unsafe { std::slice::from_raw_parts_mut(count_array, n) };
+    counts.fill(0);
+    counts[infiniDevice_t::INFINI_DEVICE_CPU as usize] = 1;
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtGetDeviceCount(
+    device: infiniDevice_t,
+    count: *mut c_int,
+) -> infiniStatus_t {
+    if count.is_null() {
+        return NULL_POINTER;
+    }
+    let n = match device {
+        infiniDevice_t::INFINI_DEVICE_CPU => 1,
+        _ => 0,
+    };
+    unsafe { *count = n };
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtSetDevice(device: infiniDevice_t, device_id: c_int) -> infiniStatus_t {
+    match (device, device_id) {
+        (infiniDevice_t::INFINI_DEVICE_CPU, 0) => {
+            CURRENT.set(device_id);
+            SUCCESS
+        }
+        (infiniDevice_t::INFINI_DEVICE_CPU, _) => DEVICE_NOT_FOUND,
+        _ => DEVICE_TYPE_NOT_SUPPORTED,
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtGetDevice(
+    device_ptr: *mut infiniDevice_t,
+    device_id_ptr: *mut c_int,
+) -> infiniStatus_t {
+    if device_ptr.is_null() || device_id_ptr.is_null() {
+        return NULL_POINTER;
+    }
+    unsafe {
+        *device_ptr = infiniDevice_t::INFINI_DEVICE_CPU;
+        *device_id_ptr = CURRENT.get();
+    }
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtDeviceSynchronize() -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtStreamCreate(stream_ptr: *mut infinirtStream_t) -> infiniStatus_t {
+    if stream_ptr.is_null() {
+        return NULL_POINTER;
+    }
+    unsafe { *stream_ptr = new_object() };
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtStreamDestroy(stream: infinirtStream_t) -> infiniStatus_t {
+    unsafe { drop_object(stream) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtStreamSynchronize(_stream: infinirtStream_t) -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtStreamWaitEvent(
+    _stream: infinirtStream_t,
+    _event: infinirtEvent_t,
+) -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtEventCreate(event_ptr: *mut infinirtEvent_t) -> infiniStatus_t {
+    if event_ptr.is_null() {
+        return NULL_POINTER;
+    }
+    unsafe { *event_ptr = new_object() };
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtEventRecord(
+    _event: infinirtEvent_t,
+    _stream: infinirtStream_t,
+) -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtEventQuery(
+    _event: infinirtEvent_t,
+    status_ptr: *mut infinirtEventStatus_t,
+) -> infiniStatus_t {
+    if status_ptr.is_null() {
+        return NULL_POINTER;
+    }
+    unsafe { *status_ptr = infinirtEventStatus_t::INFINIRT_EVENT_COMPLETE };
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtEventSynchronize(_event: infinirtEvent_t) -> infiniStatus_t {
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtEventDestroy(event: infinirtEvent_t) -> infiniStatus_t {
+    unsafe { drop_object(event) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtMalloc(p_ptr: *mut *mut c_void, size: usize) -> infiniStatus_t {
+    unsafe { alloc(p_ptr, size) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtMallocHost(p_ptr: *mut *mut c_void, size: usize) -> infiniStatus_t {
+    unsafe { alloc(p_ptr, size) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtFree(ptr: *mut c_void) -> infiniStatus_t {
+    unsafe { dealloc(ptr) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtFreeHost(ptr: *mut c_void) -> infiniStatus_t {
+    unsafe { dealloc(ptr) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtMemcpy(
+    dst: *mut c_void,
+    src: *const c_void,
+    size: usize,
+    _kind: infinirtMemcpyKind_t,
+) -> infiniStatus_t {
+    if size == 0 {
+        return SUCCESS;
+    }
+    if dst.is_null() || src.is_null() {
+        return NULL_POINTER;
+    }
+    // 设备和主机共享地址空间，各方向的复制相同；区间可能重叠（例如同一 Blob 内的 d2d）
+    unsafe { std::ptr::copy(src.cast::<u8>(), dst.cast::<u8>(), size) };
+    SUCCESS
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtMemcpyAsync(
+    dst: *mut c_void,
+    src: *const c_void,
+    size: usize,
+    kind: infinirtMemcpyKind_t,
+    stream: infinirtStream_t,
+) -> infiniStatus_t {
+    if stream.is_null() {
+        return BAD_PARAM;
+    }
+    unsafe { infinirtMemcpy(dst, src, size, kind) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtMallocAsync(
+    p_ptr: *mut *mut c_void,
+    size: usize,
+    stream: infinirtStream_t,
+) -> infiniStatus_t {
+    if stream.is_null() {
+        return BAD_PARAM;
+    }
+    unsafe { alloc(p_ptr, size) }
+}
+
+#[no_mangle]
+unsafe extern "C" fn infinirtFreeAsync(
+    ptr: *mut c_void,
+    stream: infinirtStream_t,
+) -> infiniStatus_t {
+    if stream.is_null() {
+        return BAD_PARAM;
+    }
+    unsafe { dealloc(ptr) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AsRaw, DevByte, Device, DeviceType, available_backends,
+        bindings::{infiniStatus_t, infinirtMemcpyKind_t},
+        infiniDevice_t,
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        /// 当前线程上尚未释放的分配数，所有操作都在调用线程上同步完成。
+        pub(super) static LIVE: Cell<isize> = const { Cell::new(0) };
+    }
+
+    fn read(stream: &crate::Stream, src: &[DevByte]) -> Vec<u8> {
+        let mut host = vec![0u8; src.len()];
+        stream.memcpy_d2h(&mut host, src);
+        stream.synchronize();
+        host
+    }
+
+    #[test]
+    fn device() {
+        let device = Device::default();
+        assert_eq!(device, Device::new(DeviceType::CPU, 0));
+        device.set_device();
+        assert_eq!(Device::current(), device);
+        device.synchronize();
+        assert_eq!(
+            available_backends(),
+            [(infiniDevice_t::INFINI_DEVICE_CPU, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn device_not_found() {
+        Device::new(DeviceType::CPU, 1).set_device()
+    }
+
+    #[test]
+    fn stream_memcpy() {
+        let stream = Device::default().stream();
+        assert_eq!(stream.device(), Device::default());
+
+        let src = stream.from_host(&[1u8, 2, 3, 4, 5, 6]);
+        let mut dst = stream.malloc(6);
+        stream.memcpy_d2d(&mut dst, &src);
+        assert_eq!(read(&stream, &dst), [1, 2, 3, 4, 5, 6]);
+
+        stream.memcpy_h2d(&mut dst[2..4], &[9u8, 9]);
+        assert_eq!(read(&stream, &dst), [1, 2, 9, 9, 5, 6]);
+
+        // 同一块内存内重叠的复制，只通过裸指针访问，不构造重叠的切片
+        let base = dst.as_mut_ptr().cast::<u8>();
+        let status = unsafe {
+            super::infinirtMemcpyAsync(
+                base.add(2).cast(),
+                base.cast(),
+                4,
+                infinirtMemcpyKind_t::INFINIRT_MEMCPY_D2D,
+                stream.as_raw(),
+            )
+        };
+        assert_eq!(status, infiniStatus_t::INFINI_STATUS_SUCCESS);
+        assert_eq!(read(&stream, &dst), [1, 2, 1, 2, 9, 9]);
+
+        stream.free(src);
+        stream.free(dst);
+    }
+
+    #[test]
+    fn event() {
+        let device = Device::default();
+        let stream = device.stream();
+        let mut event = device.event();
+        stream.record(&mut event);
+        stream.wait(&event);
+        assert!(event.is_complete());
+        event.synchronize();
+    }
+
+    #[test]
+    fn dev_blob() {
+        let device = Device::default();
+        let stream = device.stream();
+        let blob = device.from_host(&[10u16, 20, 30, 40]);
+        assert_eq!(blob.len(), 8);
+        assert_eq!(blob.device(), device);
+
+        let slice = blob.slice(2, 4);
+        assert_eq!(read(&stream, &slice), [20, 0, 30, 0]);
+
+        // 还有其他引用时释放只减少引用计数，数据仍然有效
+        let shared = blob.clone();
+        stream.free(blob);
+        assert_eq!(read(&stream, &shared), [10, 0, 20, 0, 30, 0, 40, 0]);
+        stream.free(shared);
+        assert_eq!(read(&stream, &slice[..2]), [20, 0]);
+        drop(slice);
+
+        let empty = device.malloc(0);
+        assert!(empty.is_empty());
+        stream.free(empty);
+    }
+
+    #[test]
+    fn empty_slice_frees_allocation() {
+        let device = Device::default();
+        let stream = device.stream();
+        let live = LIVE.get();
+
+        // 长度为 0 的子区间是最后一个引用时仍然要释放整块内存
+        let blob = device.malloc(8);
+        let empty = blob.slice(8, 0);
+        drop(blob);
+        assert_eq!(LIVE.get(), live + 1);
+        drop(empty);
+        assert_eq!(LIVE.get(), live);
+
+        let blob = stream.malloc(8);
+        let empty = blob.slice(0, 0);
+        stream.free(blob);
+        assert_eq!(LIVE.get(), live + 1);
+        stream.free(empty);
+        assert_eq!(LIVE.get(), live);
+
+        // 空分配不调用 infinirt
+        drop(device.malloc(0));
+        stream.free(stream.from_host::<u8>(&[]));
+        assert_eq!(LIVE.get(), live);
+    }
+
+    #[test]
+    #[should_panic = "blob slice out of range"]
+    fn dev_blob_slice_out_of_range() {
+        Device::default().malloc(4).slice(2, 3);
+    }
+
+    #[test]
+    fn host_blob() {
+        let device = Device::default();
+        let stream = device.stream();
+        let mut host = device.malloc_host::<u32>(4);
+        assert_eq!(host.len(), 16);
+        host.fill(7);
+
+        let mut blob = stream.malloc(16);
+        stream.memcpy_h2d(&mut blob, &host);
+        let mut back = device.malloc_host::<u8>(16);
+        stream.memcpy_d2h(&mut back, &blob);
+        stream.synchronize();
+        assert_eq!(&*back, &[7; 16]);
+
+        assert!(device.malloc_host::<u64>(0).is_empty());
+    }
+
+    #[test]
+    fn workspace() {
+        let stream = Device::default().stream();
+        let mut workspace = stream.workspace();
+        assert_eq!(workspace.capacity(), 0);
+        assert!(workspace.get(0).is_empty());
+        assert_eq!(workspace.capacity(), 0);
+
+        assert_eq!(workspace.get(64).len(), 64);
+        assert_eq!(workspace.capacity(), 64);
+        let ptr = workspace.get(64).as_ptr();
+
+        // 较小的请求复用已有的内存
+        assert_eq!(workspace.get(16).len(), 16);
+        assert_eq!(workspace.get(16).as_ptr(), ptr);
+        assert_eq!(workspace.capacity(), 64);
+
+        assert_eq!(workspace.get(128).len(), 128);
+        assert_eq!(workspace.capacity(), 128);
+        drop(workspace);
+        assert_eq!(stream.workspace().capacity(), 128);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{AsRaw, bindings::infiniStatus_t};
+use crate::{AsRaw, Error, bindings::infiniStatus_t};
 use std::ptr::null_mut;
 
 /// 一个通用的描述符包装器，用于管理底层 C 库分配的资源。
@@ -20,6 +20,19 @@ impl<T> Descriptor<T> {
         f(&mut ptr);
         Self { ptr, destroyer }
     }
+
+    /// 创建一个新的 `Descriptor` 实例，创建失败时返回错误。
+    ///
+    /// 与 [`Descriptor::new`] 相同，但闭包 `f` 返回 C API 的状态码，
+    /// 非成功的状态码将作为 [`Error::Status`] 返回。
+    pub fn try_new(
+        f: impl FnOnce(&mut *mut T) -> infiniStatus_t,
+        destroyer: unsafe extern "C" fn(*mut T) -> infiniStatus_t,
+    ) -> Result<Self, Error> {
+        let mut ptr = null_mut();
+        Error::check(f(&mut ptr))?;
+        Ok(Self { ptr, destroyer })
+    }
 }
 
 impl<T> Drop for Descriptor<T> {
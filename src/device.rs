@@ -60,4 +60,41 @@ impl Device {
     pub fn set_device(&self) {
         infini!(infinirtSetDevice(self.ty, self.id))
     }
+
+    /// 获取当前活动的 InfiniCore 设备。
+    #[inline]
+    pub fn current() -> Self {
+        let mut ty = infiniDevice_t::INFINI_DEVICE_CPU;
+        let mut id = 0;
+        infini!(infinirtGetDevice(&mut ty, &mut id));
+        Self { ty, id }
+    }
+}
+
+/// `infiniDevice_t` 中的全部设备类型，按枚举值排列。
+const DEVICE_TYPES: [infiniDevice_t; 9] = [
+    infiniDevice_t::INFINI_DEVICE_CPU,
+    infiniDevice_t::INFINI_DEVICE_NVIDIA,
+    infiniDevice_t::INFINI_DEVICE_CAMBRICON,
+    infiniDevice_t::INFINI_DEVICE_ASCEND,
+    infiniDevice_t::INFINI_DEVICE_METAX,
+    infiniDevice_t::INFINI_DEVICE_MOORE,
+    infiniDevice_t::INFINI_DEVICE_ILUVATAR,
+    infiniDevice_t::INFINI_DEVICE_KUNLUN,
+    infiniDevice_t::INFINI_DEVICE_SUGON,
+];
+
+/// 运行时可用的后端及其设备数，只包含至少有一个设备的后端。
+///
+/// 编译期可以用 `cfg(infini_nvidia)` 等判断 InfiniCore 编译了哪些后端，
+/// 这个函数进一步反映当前机器上实际存在的设备。
+pub fn available_backends() -> Vec<(infiniDevice_t, usize)> {
+    let mut counts = [0; infiniDevice_t::INFINI_DEVICE_TYPE_COUNT as usize];
+    infini!(infinirtGetAllDeviceCount(counts.as_mut_ptr()));
+    DEVICE_TYPES
+        .into_iter()
+        .zip(counts)
+        .filter(|&(_, n)| n > 0)
+        .map(|(ty, n)| (ty, n as usize))
+        .collect()
 }
@@ -0,0 +1,658 @@
+//! 运行时动态加载 InfiniCore 库，由 `dlopen` feature 启用。
+//!
+//! 与 `cpu` feature 一样，这个模块以 C 库的符号名导出 `bindings` 中声明的每个函数，
+//! 这些函数转发到 [`load`] 时从动态库中解析出的函数表，因此其他模块的调用方式不变。
+//! 函数表解析之前调用任何函数都返回 `INFINI_STATUS_DEVICE_NOT_INITIALIZED`。
+//!
+//! infinirt 的函数和 infiniop 的句柄、张量描述符函数是必需的，[`load`] 时缺少任何一个都会失败；
+//! 各个算子的函数是可选的，库中没有的算子在创建描述符时得到
+//! [`Error::Status`](crate::Error::Status)`(INFINI_STATUS_NOT_IMPLEMENTED)`。
+#![allow(non_snake_case)]
+
+use crate::bindings::*;
+use libloading::{library_filename, Library};
+use std::{
+    env::var_os,
+    ffi::{c_int, c_void},
+    fmt,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+/// 动态加载的选项。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LoadOptions {
+    /// 依次查找库文件的目录。都找不到时按库文件名交给系统的动态库搜索路径。
+    pub dirs: Vec<PathBuf>,
+    /// 要加载的库名（不含 `lib` 前缀和扩展名），启用 `ccl` feature 时包括 infiniccl。
+    pub libs: Vec<String>,
+}
+
+impl Default for LoadOptions {
+    /// `$INFINI_ROOT/lib`，然后是构建时找到的库目录；库名与构建时相同。
+    fn default() -> Self {
+        let mut dirs = Vec::new();
+        if let Some(root) = var_os("INFINI_ROOT") {
+            dirs.push(PathBuf::from(root).join("lib"))
+        }
+        if let Some(dir) = option_env!("INFINI_DLOPEN_DIR") {
+            dirs.push(dir.into())
+        }
+        Self {
+            dirs,
+            libs: env!("INFINI_DLOPEN_LIBS")
+                .split(',')
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// 动态加载 InfiniCore 失败。
+#[derive(Debug)]
+pub enum LoadError {
+    /// 找不到或无法打开库。
+    Library {
+        /// 库名。
+        name: String,
+        /// 尝试过的路径。
+        tried: Vec<PathBuf>,
+        /// 最后一次尝试的错误。
+        source: libloading::Error,
+    },
+    /// 已加载的库中没有必需的符号。
+    Symbol {
+        /// 符号名。
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Library {
+                name,
+                tried,
+                source,
+            } => write!(f, "failed to load {name} (tried {tried:?}): {source}"),
+            Self::Symbol { name } => write!(f, "symbol {name} not found in InfiniCore libraries"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Library { source, .. } => Some(source),
+            Self::Symbol { .. } => None,
+        }
+    }
+}
+
+/// 加载 InfiniCore 库并解析函数表。只在第一次成功时生效，之后的调用直接返回 `Ok`。
+pub fn load(options: &LoadOptions) -> Result<(), LoadError> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    let _guard = LOCK.lock().unwrap();
+    if LIBS.get().is_some() {
+        return Ok(());
+    }
+
+    let libs = options
+        .libs
+        .iter()
+        .map(|name| open(name, &options.dirs))
+        .collect::<Result<Vec<_>, _>>()?;
+    let core = Core::resolve(&libs)?;
+    #[cfg(feature = "ccl")]
+    let ccl = Ccl::resolve(&libs)?;
+
+    let _ = LIBS.set(libs);
+    let _ = CORE.set(core);
+    #[cfg(feature = "ccl")]
+    let _ = CCL.set(ccl);
+    Ok(())
+}
+
+/// 是否已经成功 [`load`]。
+pub fn is_loaded() -> bool {
+    LIBS.get().is_some()
+}
+
+/// 已打开的库，在进程结束前一直保持打开，以保证函数表中的指针有效。
+static LIBS: OnceLock<Vec<Library>> = OnceLock::new();
+
+fn open(name: &str, dirs: &[PathBuf]) -> Result<Library, LoadError> {
+    let file = library_filename(name);
+    let mut tried = dirs.iter().map(|dir| dir.join(&file)).collect::<Vec<_>>();
+    tried.push(file.into());
+    let mut last = None;
+    for path in &tried {
+        match unsafe { Library::new(path) } {
+            Ok(lib) => return Ok(lib),
+            Err(e) => last = Some(e),
+        }
+    }
+    Err(LoadError::Library {
+        name: name.into(),
+        tried,
+        source: last.unwrap(),
+    })
+}
+
+fn find<T: Copy>(libs: &[Library], name: &'static str) -> Result<T, LoadError> {
+    libs.iter()
+        .find_map(|lib| unsafe { lib.get::<T>(name.as_bytes()) }.ok().map(|f| *f))
+        .ok_or(LoadError::Symbol { name })
+}
+
+/// 生成函数表类型 `$table`、保存它的静态变量 `$static`，以及转发到函数表的导出函数。
+///
+/// 必需的符号在 [`load`] 时解析，缺少任何一个都是 [`LoadError::Symbol`]；
+/// `optional` 中的符号缺少时不报错，调用对应的导出函数返回 `INFINI_STATUS_NOT_IMPLEMENTED`。
+macro_rules! table {
+    ($table:ident in $static:ident {
+        $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?);)*
+    } $(optional {
+        $(fn $opt:ident($($opt_arg:ident: $opt_ty:ty),* $(,)?);)*
+    })?) => {
+        struct $table {
+            $($name: unsafe extern "C" fn($($ty),*) -> infiniStatus_t,)*
+            $($($opt: Option<unsafe extern "C" fn($($opt_ty),*) -> infiniStatus_t>,)*)?
+        }
+
+        static $static: OnceLock<$table> = OnceLock::new();
+
+        impl $table {
+            fn resolve(libs: &[Library]) -> Result<Self, LoadError> {
+                Ok(Self {
+                    $($name: find(libs, stringify!($name))?,)*
+                    $($($opt: find(libs, stringify!($opt)).ok(),)*)?
+                })
+            }
+        }
+
+        $(
+            #[no_mangle]
+            unsafe extern "C" fn $name($($arg: $ty),*) -> infiniStatus_t {
+                match $static.get() {
+                    Some(table) => unsafe { (table.$name)($($arg),*) },
+                    None => infiniStatus_t::INFINI_STATUS_DEVICE_NOT_INITIALIZED,
+                }
+            }
+        )*
+
+        $($(
+            #[no_mangle]
+            unsafe extern "C" fn $opt($($opt_arg: $opt_ty),*) -> infiniStatus_t {
+                match $static.get() {
+                    Some(table) => match table.$opt {
+                        Some(f) => unsafe { f($($opt_arg),*) },
+                        None => infiniStatus_t::INFINI_STATUS_NOT_IMPLEMENTED,
+                    },
+                    None => infiniStatus_t::INFINI_STATUS_DEVICE_NOT_INITIALIZED,
+                }
+            }
+        )*)?
+    };
+}
+
+table! {
+    Core in CORE {
+        fn infinirtInit();
+        fn infinirtGetAllDeviceCount(count_array: *mut c_int);
+        fn infinirtGetDeviceCount(device: infiniDevice_t, count: *mut c_int);
+        fn infinirtSetDevice(device: infiniDevice_t, device_id: c_int);
+        fn infinirtGetDevice(device_ptr: *mut infiniDevice_t, device_id_ptr: *mut c_int);
+        fn infinirtDeviceSynchronize();
+        fn infinirtStreamCreate(stream_ptr: *mut infinirtStream_t);
+        fn infinirtStreamDestroy(stream: infinirtStream_t);
+        fn infinirtStreamSynchronize(stream: infinirtStream_t);
+        fn infinirtStreamWaitEvent(stream: infinirtStream_t, event: infinirtEvent_t);
+        fn infinirtEventCreate(event_ptr: *mut infinirtEvent_t);
+        fn infinirtEventRecord(event: infinirtEvent_t, stream: infinirtStream_t);
+        fn infinirtEventQuery(event: infinirtEvent_t, status_ptr: *mut infinirtEventStatus_t);
+        fn infinirtEventSynchronize(event: infinirtEvent_t);
+        fn infinirtEventDestroy(event: infinirtEvent_t);
+        fn infinirtMalloc(p_ptr: *mut *mut c_void, size: usize);
+        fn infinirtMallocHost(p_ptr: *mut *mut c_void, size: usize);
+        fn infinirtFree(ptr: *mut c_void);
+        fn infinirtFreeHost(ptr: *mut c_void);
+        fn infinirtMemcpy(
+            dst: *mut c_void,
+            src: *const c_void,
+            size: usize,
+            kind: infinirtMemcpyKind_t,
+        );
+        fn infinirtMemcpyAsync(
+            dst: *mut c_void,
+            src: *const c_void,
+            size: usize,
+            kind: infinirtMemcpyKind_t,
+            stream: infinirtStream_t,
+        );
+        fn infinirtMallocAsync(p_ptr: *mut *mut c_void, size: usize, stream: infinirtStream_t);
+        fn infinirtFreeAsync(ptr: *mut c_void, stream: infinirtStream_t);
+        fn infiniopCreateHandle(handle_ptr: *mut infiniopHandle_t);
+        fn infiniopDestroyHandle(handle: infiniopHandle_t);
+        fn infiniopCreateTensorDescriptor(
+            desc_ptr: *mut infiniopTensorDescriptor_t,
+            ndim: usize,
+            shape: *const usize,
+            strides: *const isize,
+            dtype: infiniDtype_t,
+        );
+        fn infiniopDestroyTensorDescriptor(desc: infiniopTensorDescriptor_t);
+    }
+    // 不同版本、不同后端的 infiniop 提供的算子不同，缺少的算子不影响其他功能
+    optional {
+        fn infiniopGetDescriptorDeviceType(
+            desc: infiniopOperatorDescriptor_t,
+            device_type: *mut infiniDevice_t,
+        );
+        fn infiniopCreateGemmDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopGemmDescriptor_t,
+            c_desc: infiniopTensorDescriptor_t,
+            a_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetGemmWorkspaceSize(desc: infiniopGemmDescriptor_t, size: *mut usize);
+        fn infiniopGemm(
+            desc: infiniopGemmDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            c: *mut c_void,
+            a: *const c_void,
+            b: *const c_void,
+            alpha: f32,
+            beta: f32,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyGemmDescriptor(desc: infiniopGemmDescriptor_t);
+        fn infiniopCreateAddDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopAddDescriptor_t,
+            c_desc: infiniopTensorDescriptor_t,
+            a_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetAddWorkspaceSize(desc: infiniopAddDescriptor_t, size: *mut usize);
+        fn infiniopAdd(
+            desc: infiniopAddDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            c: *mut c_void,
+            a: *const c_void,
+            b: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyAddDescriptor(desc: infiniopAddDescriptor_t);
+        fn infiniopCreateSubDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopSubDescriptor_t,
+            c_desc: infiniopTensorDescriptor_t,
+            a_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetSubWorkspaceSize(desc: infiniopSubDescriptor_t, size: *mut usize);
+        fn infiniopSub(
+            desc: infiniopSubDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            c: *mut c_void,
+            a: *const c_void,
+            b: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroySubDescriptor(desc: infiniopSubDescriptor_t);
+        fn infiniopCreateMulDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopMulDescriptor_t,
+            c_desc: infiniopTensorDescriptor_t,
+            a_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetMulWorkspaceSize(desc: infiniopMulDescriptor_t, size: *mut usize);
+        fn infiniopMul(
+            desc: infiniopMulDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            c: *mut c_void,
+            a: *const c_void,
+            b: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyMulDescriptor(desc: infiniopMulDescriptor_t);
+        fn infiniopCreateSwiGLUDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopSwiGLUDescriptor_t,
+            c_desc: infiniopTensorDescriptor_t,
+            a_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetSwiGLUWorkspaceSize(desc: infiniopSwiGLUDescriptor_t, size: *mut usize);
+        fn infiniopSwiGLU(
+            desc: infiniopSwiGLUDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            c: *mut c_void,
+            a: *const c_void,
+            b: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroySwiGLUDescriptor(desc: infiniopSwiGLUDescriptor_t);
+        fn infiniopCreateReluDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopReluDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetReluWorkspaceSize(desc: infiniopReluDescriptor_t, size: *mut usize);
+        fn infiniopRelu(
+            desc: infiniopReluDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyReluDescriptor(desc: infiniopReluDescriptor_t);
+        fn infiniopCreateGeluDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopGeluDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetGeluWorkspaceSize(desc: infiniopGeluDescriptor_t, size: *mut usize);
+        fn infiniopGelu(
+            desc: infiniopGeluDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyGeluDescriptor(desc: infiniopGeluDescriptor_t);
+        fn infiniopCreateSiluDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopSiluDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetSiluWorkspaceSize(desc: infiniopSiluDescriptor_t, size: *mut usize);
+        fn infiniopSilu(
+            desc: infiniopSiluDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroySiluDescriptor(desc: infiniopSiluDescriptor_t);
+        fn infiniopCreateClipDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopClipDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            min_val_desc: infiniopTensorDescriptor_t,
+            max_val_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetClipWorkspaceSize(desc: infiniopClipDescriptor_t, size: *mut usize);
+        fn infiniopClip(
+            desc: infiniopClipDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            min_val: *const c_void,
+            max_val: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyClipDescriptor(desc: infiniopClipDescriptor_t);
+        fn infiniopCreateRMSNormDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopRMSNormDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            w_desc: infiniopTensorDescriptor_t,
+            epsilon: f32,
+        );
+        fn infiniopGetRMSNormWorkspaceSize(desc: infiniopRMSNormDescriptor_t, size: *mut usize);
+        fn infiniopRMSNorm(
+            desc: infiniopRMSNormDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            w: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyRMSNormDescriptor(desc: infiniopRMSNormDescriptor_t);
+        fn infiniopCreateLayerNormDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopLayerNormDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            w_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+            epsilon: f32,
+        );
+        fn infiniopGetLayerNormWorkspaceSize(desc: infiniopLayerNormDescriptor_t, size: *mut usize);
+        fn infiniopLayerNorm(
+            desc: infiniopLayerNormDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            w: *const c_void,
+            b: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyLayerNormDescriptor(desc: infiniopLayerNormDescriptor_t);
+        fn infiniopCreateRoPEDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopRoPEDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            pos_ids: infiniopTensorDescriptor_t,
+            sin_table: infiniopTensorDescriptor_t,
+            cos_table: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetRoPEWorkspaceSize(desc: infiniopRoPEDescriptor_t, size: *mut usize);
+        fn infiniopRoPE(
+            desc: infiniopRoPEDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            pos_ids: *const c_void,
+            sin_table: *const c_void,
+            cos_table: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyRoPEDescriptor(desc: infiniopRoPEDescriptor_t);
+        fn infiniopCreateCausalSoftmaxDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopCausalSoftmaxDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetCausalSoftmaxWorkspaceSize(
+            desc: infiniopCausalSoftmaxDescriptor_t,
+            size: *mut usize,
+        );
+        fn infiniopCausalSoftmax(
+            desc: infiniopCausalSoftmaxDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyCausalSoftmaxDescriptor(desc: infiniopCausalSoftmaxDescriptor_t);
+        fn infiniopCreateAttentionDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopAttentionDescriptor_t,
+            out_desc: infiniopTensorDescriptor_t,
+            q_desc: infiniopTensorDescriptor_t,
+            k_desc: infiniopTensorDescriptor_t,
+            v_desc: infiniopTensorDescriptor_t,
+            k_cache_desc: infiniopTensorDescriptor_t,
+            v_cache_desc: infiniopTensorDescriptor_t,
+            pos: usize,
+        );
+        fn infiniopGetAttentionWorkspaceSize(desc: infiniopAttentionDescriptor_t, size: *mut usize);
+        fn infiniopAttention(
+            desc: infiniopAttentionDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            out: *mut c_void,
+            q: *const c_void,
+            k: *const c_void,
+            v: *const c_void,
+            k_cache: *mut c_void,
+            v_cache: *mut c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyAttentionDescriptor(desc: infiniopAttentionDescriptor_t);
+        fn infiniopCreateRandomSampleDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopRandomSampleDescriptor_t,
+            result: infiniopTensorDescriptor_t,
+            probs: infiniopTensorDescriptor_t,
+        );
+        fn infiniopGetRandomSampleWorkspaceSize(
+            desc: infiniopRandomSampleDescriptor_t,
+            size: *mut usize,
+        );
+        fn infiniopRandomSample(
+            desc: infiniopRandomSampleDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            result: *mut c_void,
+            probs: *const c_void,
+            random_val: f32,
+            topp: f32,
+            topk: c_int,
+            temperature: f32,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyRandomSampleDescriptor(desc: infiniopRandomSampleDescriptor_t);
+        fn infiniopCreateReduceMaxDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopReduceMaxDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            dim: usize,
+        );
+        fn infiniopGetReduceMaxWorkspaceSize(desc: infiniopReduceMaxDescriptor_t, size: *mut usize);
+        fn infiniopReduceMax(
+            desc: infiniopReduceMaxDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyReduceMaxDescriptor(desc: infiniopReduceMaxDescriptor_t);
+        fn infiniopCreateRearrangeDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopRearrangeDescriptor_t,
+            dst: infiniopTensorDescriptor_t,
+            src: infiniopTensorDescriptor_t,
+        );
+        fn infiniopRearrange(
+            desc: infiniopRearrangeDescriptor_t,
+            dst: *mut c_void,
+            src: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyRearrangeDescriptor(desc: infiniopRearrangeDescriptor_t);
+        fn infiniopCreateConvDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopConvDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            w_desc: infiniopTensorDescriptor_t,
+            b_desc: infiniopTensorDescriptor_t,
+            pads: *mut c_void,
+            strides: *mut c_void,
+            dilations: *mut c_void,
+            n: usize,
+        );
+        fn infiniopGetConvWorkspaceSize(desc: infiniopConvDescriptor_t, size: *mut usize);
+        fn infiniopConv(
+            desc: infiniopConvDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            w: *const c_void,
+            bias: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyConvDescriptor(desc: infiniopConvDescriptor_t);
+        fn infiniopCreateAvgPoolDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopAvgPoolDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            kernel_shape: *const usize,
+            pads: *const usize,
+            strides: *const isize,
+            n: usize,
+        );
+        fn infiniopGetAvgPoolWorkspaceSize(desc: infiniopAvgPoolDescriptor_t, size: *mut usize);
+        fn infiniopAvgPool(
+            desc: infiniopAvgPoolDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyAvgPoolDescriptor(desc: infiniopAvgPoolDescriptor_t);
+        fn infiniopCreateMaxPoolDescriptor(
+            handle: infiniopHandle_t,
+            desc_ptr: *mut infiniopMaxPoolDescriptor_t,
+            y_desc: infiniopTensorDescriptor_t,
+            x_desc: infiniopTensorDescriptor_t,
+            kernel_shape: *const usize,
+            pads: *const usize,
+            strides: *const isize,
+            n: usize,
+        );
+        fn infiniopGetMaxPoolWorkspaceSize(desc: infiniopMaxPoolDescriptor_t, size: *mut usize);
+        fn infiniopMaxPool(
+            desc: infiniopMaxPoolDescriptor_t,
+            workspace: *mut c_void,
+            workspace_size: usize,
+            y: *mut c_void,
+            x: *const c_void,
+            stream: *mut c_void,
+        );
+        fn infiniopDestroyMaxPoolDescriptor(desc: infiniopMaxPoolDescriptor_t);
+    }
+}
+
+#[cfg(feature = "ccl")]
+table! {
+    Ccl in CCL {
+        fn infinicclCommInitAll(
+            device_type: infiniDevice_t,
+            comms: *mut infinicclComm_t,
+            ndevice: c_int,
+            device_ids: *const c_int,
+        );
+        fn infinicclCommDestroy(comm: infinicclComm_t);
+        fn infinicclAllReduce(
+            sendbuf: *mut c_void,
+            recvbuf: *mut c_void,
+            count: usize,
+            datatype: infiniDtype_t,
+            op: infinicclReduceOp_t,
+            comm: infinicclComm_t,
+            stream: infinirtStream_t,
+        );
+    }
+}
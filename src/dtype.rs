@@ -0,0 +1,168 @@
+use crate::infiniDtype_t;
+use digit_layout::{DigitLayout, types};
+use std::fmt;
+
+/// `digit_layout::types` 中没有预定义、但 InfiniCore 支持的数据类型。
+pub mod layouts {
+    digit_layout::layout!(BYTE = "byte"; [1] in 1);
+    digit_layout::layout!(F8 e(4)m(3));
+    digit_layout::layout!(C16 e(4)m(3); 2);
+    digit_layout::layout!(C32 e(5)m(10); 2);
+    digit_layout::layout!(C64 e(8)m(23); 2);
+    digit_layout::layout!(C128 e(11)m(52); 2);
+}
+
+/// 无法映射到 `infiniDtype_t` 的数据布局。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnsupportedLayout(pub DigitLayout);
+
+/// 无法映射到 `DigitLayout` 的 `infiniDtype_t`。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnsupportedDtype(pub infiniDtype_t);
+
+impl TryFrom<DigitLayout> for infiniDtype_t {
+    type Error = UnsupportedLayout;
+
+    fn try_from(dt: DigitLayout) -> Result<Self, Self::Error> {
+        use infiniDtype_t::*;
+        Ok(match dt {
+            layouts::BYTE => INFINI_DTYPE_BYTE,
+            types::Bool => INFINI_DTYPE_BOOL,
+            types::I8 => INFINI_DTYPE_I8,
+            types::I16 => INFINI_DTYPE_I16,
+            types::I32 => INFINI_DTYPE_I32,
+            types::I64 => INFINI_DTYPE_I64,
+            types::U8 => INFINI_DTYPE_U8,
+            types::U16 => INFINI_DTYPE_U16,
+            types::U32 => INFINI_DTYPE_U32,
+            types::U64 => INFINI_DTYPE_U64,
+            layouts::F8 => INFINI_DTYPE_F8,
+            types::F16 => INFINI_DTYPE_F16,
+            types::F32 => INFINI_DTYPE_F32,
+            types::F64 => INFINI_DTYPE_F64,
+            layouts::C16 => INFINI_DTYPE_C16,
+            layouts::C32 => INFINI_DTYPE_C32,
+            layouts::C64 => INFINI_DTYPE_C64,
+            layouts::C128 => INFINI_DTYPE_C128,
+            types::BF16 => INFINI_DTYPE_BF16,
+            _ => return Err(UnsupportedLayout(dt)),
+        })
+    }
+}
+
+impl TryFrom<infiniDtype_t> for DigitLayout {
+    type Error = UnsupportedDtype;
+
+    fn try_from(dt: infiniDtype_t) -> Result<Self, Self::Error> {
+        use infiniDtype_t::*;
+        Ok(match dt {
+            INFINI_DTYPE_BYTE => layouts::BYTE,
+            INFINI_DTYPE_BOOL => types::Bool,
+            INFINI_DTYPE_I8 => types::I8,
+            INFINI_DTYPE_I16 => types::I16,
+            INFINI_DTYPE_I32 => types::I32,
+            INFINI_DTYPE_I64 => types::I64,
+            INFINI_DTYPE_U8 => types::U8,
+            INFINI_DTYPE_U16 => types::U16,
+            INFINI_DTYPE_U32 => types::U32,
+            INFINI_DTYPE_U64 => types::U64,
+            INFINI_DTYPE_F8 => layouts::F8,
+            INFINI_DTYPE_F16 => types::F16,
+            INFINI_DTYPE_F32 => types::F32,
+            INFINI_DTYPE_F64 => types::F64,
+            INFINI_DTYPE_C16 => layouts::C16,
+            INFINI_DTYPE_C32 => layouts::C32,
+            INFINI_DTYPE_C64 => layouts::C64,
+            INFINI_DTYPE_C128 => layouts::C128,
+            INFINI_DTYPE_BF16 => types::BF16,
+            _ => return Err(UnsupportedDtype(dt)),
+        })
+    }
+}
+
+impl fmt::Display for UnsupportedLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "data layout {} has no infiniDtype_t counterpart", self.0)
+    }
+}
+
+impl fmt::Display for UnsupportedDtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} has no DigitLayout counterpart", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedLayout {}
+impl std::error::Error for UnsupportedDtype {}
+
+#[cfg(test)]
+mod tests {
+    use super::{UnsupportedDtype, UnsupportedLayout, layouts};
+    use crate::infiniDtype_t::{self, *};
+    use digit_layout::{DigitLayout, LayoutContent, types};
+
+    const TABLE: &[(DigitLayout, infiniDtype_t)] = &[
+        (layouts::BYTE, INFINI_DTYPE_BYTE),
+        (types::Bool, INFINI_DTYPE_BOOL),
+        (types::I8, INFINI_DTYPE_I8),
+        (types::I16, INFINI_DTYPE_I16),
+        (types::I32, INFINI_DTYPE_I32),
+        (types::I64, INFINI_DTYPE_I64),
+        (types::U8, INFINI_DTYPE_U8),
+        (types::U16, INFINI_DTYPE_U16),
+        (types::U32, INFINI_DTYPE_U32),
+        (types::U64, INFINI_DTYPE_U64),
+        (layouts::F8, INFINI_DTYPE_F8),
+        (types::F16, INFINI_DTYPE_F16),
+        (types::F32, INFINI_DTYPE_F32),
+        (types::F64, INFINI_DTYPE_F64),
+        (layouts::C16, INFINI_DTYPE_C16),
+        (layouts::C32, INFINI_DTYPE_C32),
+        (layouts::C64, INFINI_DTYPE_C64),
+        (layouts::C128, INFINI_DTYPE_C128),
+        (types::BF16, INFINI_DTYPE_BF16),
+    ];
+
+    #[test]
+    fn round_trip() {
+        for &(layout, dtype) in TABLE {
+            assert_eq!(infiniDtype_t::try_from(layout), Ok(dtype));
+            assert_eq!(DigitLayout::try_from(dtype), Ok(layout));
+        }
+    }
+
+    #[test]
+    fn f8_is_e4m3() {
+        let e4m3 = LayoutContent::Real {
+            exponent: 4,
+            mantissa: 3,
+        };
+        assert_eq!(layouts::F8.decode(), e4m3);
+        assert_eq!(layouts::F8.nbytes(), 1);
+        // C16 是两个 e4m3 组成的复数
+        assert_eq!(layouts::C16.decode(), e4m3);
+        assert_eq!((layouts::C16.group_size(), layouts::C16.nbytes()), (2, 2));
+        // e5m2 不是 InfiniCore 的 F8
+        let e5m2 = DigitLayout::real(5, 2, 1);
+        assert_eq!(infiniDtype_t::try_from(e5m2), Err(UnsupportedLayout(e5m2)));
+    }
+
+    #[test]
+    fn unsupported() {
+        for layout in [
+            types::U128,
+            types::I128,
+            types::F128,
+            DigitLayout::real(8, 23, 4),
+        ] {
+            assert_eq!(
+                infiniDtype_t::try_from(layout),
+                Err(UnsupportedLayout(layout))
+            );
+        }
+        assert_eq!(
+            DigitLayout::try_from(INFINI_DTYPE_INVALID),
+            Err(UnsupportedDtype(INFINI_DTYPE_INVALID))
+        );
+    }
+}
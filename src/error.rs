@@ -0,0 +1,52 @@
+use crate::{Device, bindings::infiniStatus_t};
+use std::fmt;
+
+/// InfiniCore 操作的错误。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// 底层 C 库返回了非成功的状态码。
+    Status(infiniStatus_t),
+    /// 张量形状不满足算子要求。
+    BadShape(String),
+    /// 张量步长不满足算子要求。
+    BadStrides(String),
+    /// 张量数据类型不满足算子要求。
+    BadDtype(String),
+    /// 这种参数组合还没有在设备上的实现。
+    NotImplemented(String),
+    /// 流或张量与句柄不在同一设备上。
+    DeviceMismatch {
+        /// 句柄所在的设备。
+        expected: Device,
+        /// 实际遇到的设备。
+        found: Device,
+    },
+}
+
+impl Error {
+    /// 将底层 C 库返回的状态码转换为 `Result`。
+    #[inline]
+    pub fn check(status: infiniStatus_t) -> Result<(), Self> {
+        match status {
+            infiniStatus_t::INFINI_STATUS_SUCCESS => Ok(()),
+            status => Err(Self::Status(status)),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Status(status) => write!(f, "infini call failed with {status:?}"),
+            Self::BadShape(msg) => write!(f, "bad tensor shape: {msg}"),
+            Self::BadStrides(msg) => write!(f, "bad tensor strides: {msg}"),
+            Self::BadDtype(msg) => write!(f, "bad tensor dtype: {msg}"),
+            Self::NotImplemented(msg) => write!(f, "not implemented: {msg}"),
+            Self::DeviceMismatch { expected, found } => {
+                write!(f, "expected device {expected:?}, found {found:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -9,8 +9,9 @@ use std::ptr::null_mut;
 pub struct Event(infinirtEvent_t);
 
 impl Device {
-    /// 创建一个与当前设备上下文关联的新事件。
+    /// 激活此设备并在其上创建一个新事件。
     pub fn event(&self) -> Event {
+        self.set_device();
         let mut event = null_mut();
         infini!(infinirtEventCreate(&mut event));
         Event(event)
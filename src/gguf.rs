@@ -0,0 +1,788 @@
+use crate::{DevTensor, Device, Stream, Tensor, bindings::infiniDtype_t};
+use digit_layout::{DigitLayout, types};
+use memmap2::Mmap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::File,
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// 一个以内存映射方式打开的 GGUF 模型文件。
+///
+/// 只支持 GGUF v2 和 v3。元数据和张量信息在打开时解析，张量数据在 [`Gguf::load`] 或
+/// [`Gguf::load_all`] 时才上传到设备。
+pub struct Gguf {
+    mmap: Mmap,
+    version: u32,
+    metadata: HashMap<String, MetaValue>,
+    tensors: BTreeMap<String, TensorInfo>,
+}
+
+/// GGUF 元数据的值。
+#[derive(Clone, PartialEq, Debug)]
+pub enum MetaValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<MetaValue>),
+}
+
+impl MetaValue {
+    /// 字符串值。
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// 非负整数值，任意宽度的整数都会尝试转换。
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::U8(v) => Some(v as _),
+            Self::U16(v) => Some(v as _),
+            Self::U32(v) => Some(v as _),
+            Self::U64(v) => Some(v),
+            Self::I8(v) => v.try_into().ok(),
+            Self::I16(v) => v.try_into().ok(),
+            Self::I32(v) => v.try_into().ok(),
+            Self::I64(v) => v.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    /// 浮点值，整数也会转换为浮点数。
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::F32(v) => Some(v as _),
+            Self::F64(v) => Some(v),
+            Self::I8(v) => Some(v as _),
+            Self::I16(v) => Some(v as _),
+            Self::I32(v) => Some(v as _),
+            Self::I64(v) => Some(v as _),
+            _ => self.as_u64().map(|v| v as _),
+        }
+    }
+
+    /// 布尔值。
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// 数组值。
+    pub fn as_array(&self) -> Option<&[MetaValue]> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// GGUF 张量的数据类型 (`ggml_type`)。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GgmlType {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q5_0,
+    Q5_1,
+    Q8_0,
+    Q8_1,
+    Q2K,
+    Q3K,
+    Q4K,
+    Q5K,
+    Q6K,
+    Q8K,
+    IQ2XXS,
+    IQ2XS,
+    IQ3XXS,
+    IQ1S,
+    IQ4NL,
+    IQ3S,
+    IQ2S,
+    IQ4XS,
+    I8,
+    I16,
+    I32,
+    I64,
+    F64,
+    IQ1M,
+    BF16,
+    TQ1_0,
+    TQ2_0,
+    MXFP4,
+    /// 本 crate 不认识的类型编号。
+    Unknown(u32),
+}
+
+impl From<u32> for GgmlType {
+    fn from(ty: u32) -> Self {
+        use GgmlType::*;
+        match ty {
+            0 => F32,
+            1 => F16,
+            2 => Q4_0,
+            3 => Q4_1,
+            6 => Q5_0,
+            7 => Q5_1,
+            8 => Q8_0,
+            9 => Q8_1,
+            10 => Q2K,
+            11 => Q3K,
+            12 => Q4K,
+            13 => Q5K,
+            14 => Q6K,
+            15 => Q8K,
+            16 => IQ2XXS,
+            17 => IQ2XS,
+            18 => IQ3XXS,
+            19 => IQ1S,
+            20 => IQ4NL,
+            21 => IQ3S,
+            22 => IQ2S,
+            23 => IQ4XS,
+            24 => I8,
+            25 => I16,
+            26 => I32,
+            27 => I64,
+            28 => F64,
+            29 => IQ1M,
+            30 => BF16,
+            34 => TQ1_0,
+            35 => TQ2_0,
+            39 => MXFP4,
+            _ => Unknown(ty),
+        }
+    }
+}
+
+impl GgmlType {
+    /// 每块的元素数和字节数，非量化类型的块就是一个元素。
+    pub fn block(self) -> Option<(usize, usize)> {
+        use GgmlType::*;
+        Some(match self {
+            F32 | I32 => (1, 4),
+            F16 | BF16 | I16 => (1, 2),
+            F64 | I64 => (1, 8),
+            I8 => (1, 1),
+            Q4_0 | IQ4NL => (32, 18),
+            Q4_1 => (32, 20),
+            Q5_0 => (32, 22),
+            Q5_1 => (32, 24),
+            Q8_0 => (32, 34),
+            Q8_1 => (32, 36),
+            MXFP4 => (32, 17),
+            Q2K => (256, 84),
+            Q3K | IQ3S => (256, 110),
+            Q4K => (256, 144),
+            Q5K => (256, 176),
+            Q6K => (256, 210),
+            Q8K => (256, 292),
+            IQ2XXS | TQ2_0 => (256, 66),
+            IQ2XS => (256, 74),
+            IQ3XXS => (256, 98),
+            IQ1S => (256, 50),
+            IQ2S => (256, 82),
+            IQ4XS => (256, 136),
+            IQ1M => (256, 56),
+            TQ1_0 => (256, 54),
+            Unknown(_) => return None,
+        })
+    }
+
+    /// 对应的 `DigitLayout`，量化类型没有对应。
+    pub fn layout(self) -> Option<DigitLayout> {
+        use GgmlType::*;
+        Some(match self {
+            F32 => types::F32,
+            F16 => types::F16,
+            BF16 => types::BF16,
+            F64 => types::F64,
+            I8 => types::I8,
+            I16 => types::I16,
+            I32 => types::I32,
+            I64 => types::I64,
+            _ => return None,
+        })
+    }
+
+    /// 对应的 `infiniDtype_t`，量化类型没有对应。
+    pub fn dtype(self) -> Option<infiniDtype_t> {
+        self.layout()?.try_into().ok()
+    }
+}
+
+/// GGUF 文件中一个张量的信息。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TensorInfo {
+    /// 数据类型。
+    pub ty: GgmlType,
+    /// 行优先的形状，即 GGUF 中维度的逆序。
+    pub shape: Vec<usize>,
+    range: Option<Range<usize>>,
+}
+
+impl TensorInfo {
+    /// 张量数据的字节数，类型未知时为 `None`。
+    #[inline]
+    pub fn nbytes(&self) -> Option<usize> {
+        self.range.as_ref().map(Range::len)
+    }
+}
+
+/// 读取 GGUF 文件失败。
+#[derive(Debug)]
+pub enum GgufError {
+    /// 无法打开或映射文件。
+    Io {
+        /// 出错的文件。
+        path: PathBuf,
+        /// 底层的 IO 错误。
+        source: io::Error,
+    },
+    /// 文件不符合 GGUF 格式。
+    Format {
+        /// 出错的文件。
+        path: PathBuf,
+        /// 具体原因。
+        msg: String,
+    },
+    /// 张量是量化类型或未知类型，运行时无法直接使用。
+    Unsupported {
+        /// 张量名。
+        name: String,
+        /// 张量的类型。
+        ty: GgmlType,
+    },
+    /// 没有这个名字的张量。
+    NotFound(String),
+}
+
+impl fmt::Display for GgufError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::Format { path, msg } => write!(f, "invalid gguf {}: {msg}", path.display()),
+            Self::Unsupported { name, ty } => write!(
+                f,
+                "tensor {name} has type {ty:?} which has no InfiniCore dtype, dequantize it first",
+            ),
+            Self::NotFound(name) => write!(f, "tensor {name} not found"),
+        }
+    }
+}
+
+impl std::error::Error for GgufError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// 未指定 `general.alignment` 时数据区的对齐。
+const DEFAULT_ALIGNMENT: usize = 32;
+
+/// 元数据中数组嵌套的最大层数，防止构造的文件耗尽栈空间。
+const MAX_ARRAY_DEPTH: usize = 8;
+
+impl Gguf {
+    /// 打开并解析一个 GGUF 文件。
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GgufError> {
+        let path = path.as_ref();
+        let io = |source| GgufError::Io {
+            path: path.into(),
+            source,
+        };
+        let file = File::open(path).map_err(io)?;
+        // SAFETY: 映射期间文件不应被修改
+        let mmap = unsafe { Mmap::map(&file) }.map_err(io)?;
+        Self::parse(mmap).map_err(|msg| GgufError::Format {
+            path: path.into(),
+            msg,
+        })
+    }
+
+    fn parse(mmap: Mmap) -> Result<Self, String> {
+        let mut r = Reader { buf: &mmap, pos: 0 };
+        if r.bytes(4)? != b"GGUF" {
+            return Err("bad magic".into());
+        }
+        let version = r.u32()?;
+        if !matches!(version, 2 | 3) {
+            return Err(format!("unsupported version {version}"));
+        }
+        let tensor_count = r.u64()?;
+        let kv_count = r.u64()?;
+
+        let mut metadata = HashMap::new();
+        for _ in 0..kv_count {
+            let key = r.string()?;
+            let ty = r.u32()?;
+            let value = r.value(ty, 0)?;
+            metadata.insert(key, value);
+        }
+
+        let mut infos = Vec::new();
+        for _ in 0..tensor_count {
+            let name = r.string()?;
+            let ndim = r.u32()?;
+            let mut shape = (0..ndim)
+                .map(|_| r.usize())
+                .collect::<Result<Vec<_>, _>>()?;
+            shape.reverse();
+            let ty = GgmlType::from(r.u32()?);
+            let offset = r.usize()?;
+            infos.push((name, ty, shape, offset));
+        }
+
+        let align = match metadata.get("general.alignment") {
+            Some(v) => match v.as_u64() {
+                Some(a) if a > 0 => a as usize,
+                _ => return Err(format!("bad general.alignment {v:?}")),
+            },
+            None => DEFAULT_ALIGNMENT,
+        };
+        let base = r
+            .pos
+            .checked_next_multiple_of(align)
+            .ok_or_else(|| format!("bad general.alignment {align}"))?;
+
+        let mut tensors = BTreeMap::new();
+        for (name, ty, shape, offset) in infos {
+            if offset % align != 0 {
+                return Err(format!(
+                    "tensor {name} offset {offset} is not aligned to {align}"
+                ));
+            }
+            let range = match ty.block() {
+                Some((block, size)) => {
+                    if shape.last().is_some_and(|&d| d % block != 0) {
+                        return Err(format!(
+                            "tensor {name} of {ty:?} {shape:?} is not a whole number of blocks",
+                        ));
+                    }
+                    let range = shape
+                        .iter()
+                        .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+                        .and_then(|numel| (numel / block).checked_mul(size))
+                        .and_then(|nbytes| {
+                            let start = base.checked_add(offset)?;
+                            Some(start..start.checked_add(nbytes)?)
+                        });
+                    match range {
+                        Some(range) if range.end <= mmap.len() => Some(range),
+                        _ => return Err(format!("tensor {name} data exceeds file")),
+                    }
+                }
+                None => None,
+            };
+            if tensors
+                .insert(name.clone(), TensorInfo { ty, shape, range })
+                .is_some()
+            {
+                return Err(format!("duplicate tensor {name}"));
+            }
+        }
+
+        Ok(Self {
+            mmap,
+            version,
+            metadata,
+            tensors,
+        })
+    }
+
+    /// GGUF 格式版本。
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// 所有元数据。
+    #[inline]
+    pub fn metadata(&self) -> &HashMap<String, MetaValue> {
+        &self.metadata
+    }
+
+    /// 键为 `key` 的元数据。
+    #[inline]
+    pub fn meta(&self, key: &str) -> Option<&MetaValue> {
+        self.metadata.get(key)
+    }
+
+    /// 所有张量的名字和信息，按名字排序。
+    pub fn tensors(&self) -> impl Iterator<Item = (&str, &TensorInfo)> {
+        self.tensors.iter().map(|(name, info)| (&**name, info))
+    }
+
+    /// 名为 `name` 的张量的信息。
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&TensorInfo> {
+        self.tensors.get(name)
+    }
+
+    /// 名为 `name` 的张量在映射内存中的数据，量化张量返回原始的块数据。
+    pub fn data(&self, name: &str) -> Result<&[u8], GgufError> {
+        let info = self.info(name)?;
+        match &info.range {
+            Some(range) => Ok(&self.mmap[range.clone()]),
+            None => Err(GgufError::Unsupported {
+                name: name.into(),
+                ty: info.ty,
+            }),
+        }
+    }
+
+    fn info(&self, name: &str) -> Result<&TensorInfo, GgufError> {
+        self.tensors
+            .get(name)
+            .ok_or_else(|| GgufError::NotFound(name.into()))
+    }
+
+    /// 名为 `name` 的张量的描述符和数据，张量是量化类型时返回 [`GgufError::Unsupported`]。
+    fn tensor(&self, name: &str) -> Result<(Tensor, &[u8]), GgufError> {
+        let info = self.info(name)?;
+        let Some(dt) = info.ty.layout() else {
+            return Err(GgufError::Unsupported {
+                name: name.into(),
+                ty: info.ty,
+            });
+        };
+        let desc = Tensor::contiguous(dt, info.shape.iter().copied());
+        Ok((desc, self.data(name)?))
+    }
+
+    /// 将名为 `name` 的张量同步上传到 `device`。
+    pub fn load(&self, name: &str, device: &Device) -> Result<DevTensor, GgufError> {
+        let (desc, data) = self.tensor(name)?;
+        Ok(DevTensor::new(desc, device.from_host(data)))
+    }
+
+    /// 在 `stream` 上把所有张量上传到设备，返回时复制已完成。
+    ///
+    /// 只要有一个张量是量化类型就返回 [`GgufError::Unsupported`]，不上传任何张量。
+    pub fn load_all(&self, stream: &Stream) -> Result<BTreeMap<String, DevTensor>, GgufError> {
+        let tensors = self
+            .tensors
+            .keys()
+            .map(|name| Ok((name, self.tensor(name)?)))
+            .collect::<Result<Vec<_>, GgufError>>()?;
+        let ans = tensors
+            .into_iter()
+            .map(|(name, (desc, data))| {
+                (name.clone(), DevTensor::new(desc, stream.from_host(data)))
+            })
+            .collect();
+        stream.synchronize();
+        Ok(ans)
+    }
+}
+
+/// 类型为 `ty` 的元数据值编码后的最小字节数。
+fn min_encoded_size(ty: u32) -> Result<usize, String> {
+    Ok(match ty {
+        0 | 1 | 7 => 1,
+        2 | 3 => 2,
+        4..=6 => 4,
+        // 字符串至少有 8 字节的长度
+        8 | 10..=12 => 8,
+        // 数组至少有元素类型和长度
+        9 => 12,
+        _ => return Err(format!("unknown metadata type {ty}")),
+    })
+}
+
+/// 按 GGUF 的小端编码读取文件头。
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let ans = self
+            .pos
+            .checked_add(n)
+            .and_then(|end| self.buf.get(self.pos..end))
+            .ok_or_else(|| format!("unexpected end of file at {}", self.pos))?;
+        self.pos += n;
+        Ok(ans)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// 读取一个 `u64` 长度或偏移，超出 `usize` 时报错。
+    fn usize(&mut self) -> Result<usize, String> {
+        let pos = self.pos;
+        let v = self.u64()?;
+        v.try_into()
+            .map_err(|_| format!("value {v} at {pos} exceeds address space"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.usize()?;
+        let bytes = self.bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| format!("bad string: {e}"))
+    }
+
+    /// 读取类型为 `ty` 的元数据值，`depth` 是当前所在数组的嵌套层数。
+    fn value(&mut self, ty: u32, depth: usize) -> Result<MetaValue, String> {
+        use MetaValue::*;
+        Ok(match ty {
+            0 => U8(self.array::<1>()?[0]),
+            1 => I8(i8::from_le_bytes(self.array()?)),
+            2 => U16(u16::from_le_bytes(self.array()?)),
+            3 => I16(i16::from_le_bytes(self.array()?)),
+            4 => U32(self.u32()?),
+            5 => I32(i32::from_le_bytes(self.array()?)),
+            6 => F32(f32::from_le_bytes(self.array()?)),
+            7 => Bool(self.array::<1>()?[0] != 0),
+            8 => String(self.string()?),
+            9 => {
+                if depth == MAX_ARRAY_DEPTH {
+                    return Err(format!(
+                        "arrays nested deeper than {MAX_ARRAY_DEPTH} at {}",
+                        self.pos
+                    ));
+                }
+                let ty = self.u32()?;
+                let len = self.usize()?;
+                // 长度来自文件，最多按剩余字节能编码的元素数预先分配
+                let remaining = (self.buf.len() - self.pos) / min_encoded_size(ty)?;
+                let mut items = Vec::with_capacity(len.min(remaining));
+                for _ in 0..len {
+                    items.push(self.value(ty, depth + 1)?)
+                }
+                Array(items)
+            }
+            10 => U64(self.u64()?),
+            11 => I64(i64::from_le_bytes(self.array()?)),
+            12 => F64(f64::from_le_bytes(self.array()?)),
+            _ => return Err(format!("unknown metadata type {ty}")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GgmlType, Gguf, GgufError, MetaValue};
+    use std::{fs, path::PathBuf};
+
+    /// 按 GGUF v3 的小端编码拼出文件内容。
+    #[derive(Default)]
+    struct Builder {
+        kvs: Vec<u8>,
+        kv_count: u64,
+        infos: Vec<u8>,
+        tensor_count: u64,
+    }
+
+    fn string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        buf.extend_from_slice(s.as_bytes())
+    }
+
+    impl Builder {
+        /// 键为 `key`、类型为 `ty` 的元数据，`value` 是已编码的值。
+        fn kv(mut self, key: &str, ty: u32, value: &[u8]) -> Self {
+            string(&mut self.kvs, key);
+            self.kvs.extend_from_slice(&ty.to_le_bytes());
+            self.kvs.extend_from_slice(value);
+            self.kv_count += 1;
+            self
+        }
+
+        /// `dims` 按 GGUF 的顺序，即最内层维度在前。
+        fn tensor(mut self, name: &str, dims: &[u64], ty: u32, offset: u64) -> Self {
+            string(&mut self.infos, name);
+            self.infos
+                .extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for d in dims {
+                self.infos.extend_from_slice(&d.to_le_bytes())
+            }
+            self.infos.extend_from_slice(&ty.to_le_bytes());
+            self.infos.extend_from_slice(&offset.to_le_bytes());
+            self.tensor_count += 1;
+            self
+        }
+
+        /// 文件头按 `align` 对齐后接上数据区 `data`。
+        fn build(self, align: usize, data: &[u8]) -> Vec<u8> {
+            let mut buf = b"GGUF".to_vec();
+            buf.extend_from_slice(&3u32.to_le_bytes());
+            buf.extend_from_slice(&self.tensor_count.to_le_bytes());
+            buf.extend_from_slice(&self.kv_count.to_le_bytes());
+            buf.extend_from_slice(&self.kvs);
+            buf.extend_from_slice(&self.infos);
+            buf.resize(buf.len().next_multiple_of(align), 0);
+            buf.extend_from_slice(data);
+            buf
+        }
+    }
+
+    /// 把 `bytes` 写入测试专用的临时文件后打开。
+    fn open(name: &str, bytes: &[u8]) -> Result<Gguf, GgufError> {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("infinicore-{name}-{}.gguf", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        let ans = Gguf::open(&path);
+        fs::remove_file(path).unwrap();
+        ans
+    }
+
+    fn assert_format(ans: Result<Gguf, GgufError>, expected: &str) {
+        match ans {
+            Err(GgufError::Format { msg, .. }) => assert!(msg.contains(expected), "{msg}"),
+            Err(e) => panic!("unexpected error {e}"),
+            Ok(_) => panic!("expected format error"),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let mut arch = Vec::new();
+        string(&mut arch, "llama");
+        let mut tokens = 8u32.to_le_bytes().to_vec();
+        tokens.extend_from_slice(&2u64.to_le_bytes());
+        string(&mut tokens, "a");
+        string(&mut tokens, "b");
+
+        let data = (0..96u8).collect::<Vec<_>>();
+        let bytes = Builder::default()
+            .kv("general.architecture", 8, &arch)
+            .kv("general.alignment", 4, &16u32.to_le_bytes())
+            .kv("llama.rope.freq_base", 6, &10000f32.to_le_bytes())
+            .kv("tokenizer.ggml.tokens", 9, &tokens)
+            .tensor("w", &[3, 2], 1, 0)
+            .tensor("q", &[32], 8, 16)
+            .tensor("x", &[4], 0, 64)
+            .tensor("u", &[4], 99, 80)
+            .build(16, &data);
+        let gguf = open("parse", &bytes).unwrap();
+
+        assert_eq!(gguf.version(), 3);
+        assert_eq!(
+            gguf.meta("general.architecture")
+                .and_then(MetaValue::as_str),
+            Some("llama")
+        );
+        assert_eq!(
+            gguf.meta("llama.rope.freq_base")
+                .and_then(MetaValue::as_f64),
+            Some(10000.)
+        );
+        assert_eq!(
+            gguf.meta("tokenizer.ggml.tokens")
+                .and_then(MetaValue::as_array)
+                .map(|a| a.iter().filter_map(MetaValue::as_str).collect::<Vec<_>>()),
+            Some(vec!["a", "b"])
+        );
+
+        assert_eq!(
+            gguf.tensors().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["q", "u", "w", "x"]
+        );
+        let w = gguf.get("w").unwrap();
+        assert_eq!(
+            (w.ty, &*w.shape, w.nbytes()),
+            (GgmlType::F16, &[2, 3][..], Some(12))
+        );
+        assert_eq!(gguf.data("w").unwrap(), &data[..12]);
+        assert_eq!(gguf.data("x").unwrap(), &data[64..80]);
+        // 量化张量只有原始块数据
+        assert_eq!(gguf.get("q").unwrap().nbytes(), Some(34));
+        assert_eq!(gguf.data("q").unwrap(), &data[16..50]);
+        // 未知类型的张量没有数据范围
+        let u = gguf.get("u").unwrap();
+        assert_eq!((u.ty, u.nbytes()), (GgmlType::Unknown(99), None));
+        assert!(matches!(gguf.data("u"), Err(GgufError::Unsupported { .. })));
+        assert!(matches!(gguf.data("v"), Err(GgufError::NotFound(_))));
+    }
+
+    #[test]
+    fn reject_misaligned() {
+        let bytes = Builder::default()
+            .tensor("w", &[4], 0, 8)
+            .build(32, &[0; 32]);
+        assert_format(open("misaligned", &bytes), "not aligned");
+    }
+
+    #[test]
+    fn reject_overflow() {
+        let huge = u64::MAX / 2;
+        let bytes = Builder::default()
+            .tensor("w", &[huge, 4], 0, 0)
+            .build(32, &[0; 32]);
+        assert_format(open("overflow", &bytes), "exceeds file");
+
+        let bytes = Builder::default()
+            .tensor("w", &[4], 0, u64::MAX - 31)
+            .build(32, &[0; 32]);
+        assert_format(open("offset", &bytes), "exceeds");
+    }
+
+    #[test]
+    fn reject_deep_arrays() {
+        // 每层是只有一个元素的数组，元素类型还是数组
+        let mut value = Vec::new();
+        for _ in 0..64 {
+            value.extend_from_slice(&9u32.to_le_bytes());
+            value.extend_from_slice(&1u64.to_le_bytes());
+        }
+        let bytes = Builder::default().kv("deep", 9, &value).build(32, &[]);
+        assert_format(open("deep", &bytes), "nested deeper");
+    }
+
+    #[test]
+    fn reject_huge_array_len() {
+        // 声明 2^40 个字符串但没有数据，预分配不应超过文件能容纳的元素数
+        let mut value = 8u32.to_le_bytes().to_vec();
+        value.extend_from_slice(&(1u64 << 40).to_le_bytes());
+        let bytes = Builder::default().kv("huge", 9, &value).build(32, &[]);
+        assert_format(open("huge", &bytes), "unexpected end of file");
+
+        let mut value = 99u32.to_le_bytes().to_vec();
+        value.extend_from_slice(&1u64.to_le_bytes());
+        let bytes = Builder::default().kv("unknown", 9, &value).build(32, &[]);
+        assert_format(open("unknown", &bytes), "unknown metadata type 99");
+    }
+
+    #[test]
+    fn reject_truncated() {
+        let bytes = Builder::default()
+            .tensor("w", &[16], 0, 0)
+            .build(32, &[0; 32]);
+        assert_format(open("truncated", &bytes), "exceeds file");
+        assert_format(open("magic", b"GGML\x03\0\0\0"), "bad magic");
+        assert_format(open("short", &bytes[..20]), "unexpected end of file");
+    }
+}
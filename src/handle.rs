@@ -1,16 +1,116 @@
-use crate::{AsRaw, bindings::infiniopHandle_t};
-use std::ptr::null_mut;
+use crate::{
+    AsRaw, CacheStats, DevTensor, Device, Error, Stream, Tensor,
+    bindings::infiniopHandle_t,
+    cache::{Key, OpCache},
+};
+use std::{any::Any, ptr::null_mut, sync::Arc, sync::Mutex};
 
 /// 一个 InfiniCore 操作句柄 (`infiniopHandle_t`)。
-#[repr(transparent)]
-pub struct Handle(infiniopHandle_t);
+///
+/// 句柄内置一个算子描述符缓存（参见 [`Handle::cached`]），
+/// `Handle` 上的算子方法通过它复用相同布局的描述符。
+///
+/// 句柄记录创建时所在的设备，`Handle` 上的算子方法要求流和张量都在这个设备上。
+pub struct Handle {
+    raw: infiniopHandle_t,
+    device: Device,
+    cache: Mutex<OpCache>,
+}
 
 impl Handle {
-    /// 创建一个新的 InfiniCore 操作句柄。
+    /// 描述符缓存的默认容量。
+    pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+    /// 在当前活动的设备上创建一个新的 InfiniCore 操作句柄。
     pub fn new() -> Self {
         let mut ptr = null_mut();
         infini!(infiniopCreateHandle(&mut ptr));
-        Self(ptr)
+        Self {
+            raw: ptr,
+            device: Device::current(),
+            cache: Mutex::new(OpCache::new(Self::DEFAULT_CACHE_CAPACITY)),
+        }
+    }
+
+    /// 句柄所在的设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// 设置描述符缓存的容量，超出的部分按最近最少使用淘汰。容量为 0 时禁用缓存。
+    pub fn set_cache_capacity(&self, capacity: usize) {
+        self.cache.lock().unwrap().set_capacity(capacity)
+    }
+
+    /// 描述符缓存的统计信息。
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.lock().unwrap().stats()
+    }
+
+    /// 清空描述符缓存。
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear()
+    }
+
+    /// 按算子类型 `O`、`tensors` 的数据类型、形状和步长以及 `args` 查找缓存的算子，
+    /// 未命中时调用 `create` 创建并缓存。
+    ///
+    /// `args` 用于区分影响描述符的标量参数（例如 `epsilon` 的位模式）。
+    ///
+    /// `create` 在不持有缓存锁的情况下执行，创建描述符较慢时不阻塞其他线程使用缓存。
+    pub fn cached<O: Any + Send + Sync>(
+        &self,
+        tensors: &[&Tensor],
+        args: &[u64],
+        create: impl FnOnce() -> Result<O, Error>,
+    ) -> Result<Arc<O>, Error> {
+        let key = Key::new::<O>(tensors, args);
+        if let Some(op) = self.cache.lock().unwrap().get(&key) {
+            return Ok(op);
+        }
+        let op = Arc::new(create()?);
+        Ok(self.cache.lock().unwrap().insert(key, op))
+    }
+
+    /// 检查 `stream` 和 `tensors` 都在句柄所在的设备上，然后通过 [`Handle::cached`] 取得算子。
+    pub(crate) fn prepare<O: Any + Send + Sync>(
+        &self,
+        stream: &Stream,
+        tensors: &[&DevTensor],
+        args: &[u64],
+        create: impl FnOnce() -> Result<O, Error>,
+    ) -> Result<Arc<O>, Error> {
+        self.check_devices(stream, tensors)?;
+        let tensors = tensors.iter().map(|t| t.desc()).collect::<Vec<_>>();
+        self.cached(&tensors, args, create)
+    }
+
+    /// 检查 `stream` 和 `tensors` 都在句柄所在的设备上。
+    pub(crate) fn check_devices(
+        &self,
+        stream: &Stream,
+        tensors: &[&DevTensor],
+    ) -> Result<(), Error> {
+        let devices = tensors.iter().map(|t| t.device());
+        match std::iter::once(stream.device())
+            .chain(devices)
+            .find(|&d| d != self.device)
+        {
+            Some(found) => Err(Error::DeviceMismatch {
+                expected: self.device,
+                found,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Device {
+    /// 激活此设备并在其上创建一个操作句柄。
+    pub fn handle(&self) -> Handle {
+        self.set_device();
+        Handle::new()
     }
 }
 
@@ -22,7 +122,9 @@ impl Default for Handle {
 
 impl Drop for Handle {
     fn drop(&mut self) {
-        infini!(infiniopDestroyHandle(self.0))
+        // 缓存的描述符必须先于句柄销毁
+        self.cache.get_mut().unwrap().clear();
+        infini!(infiniopDestroyHandle(self.raw))
     }
 }
 
@@ -33,6 +135,6 @@ impl AsRaw for Handle {
     type Raw = infiniopHandle_t;
     #[inline]
     unsafe fn as_raw(&self) -> Self::Raw {
-        self.0
+        self.raw
     }
 }
@@ -1,9 +1,14 @@
 //! InfiniCore Rust 核心库绑定。
 //!
 //! 这个 crate 提供了对底层 InfiniCore C 库（infinirt 和 infiniop）的安全 Rust 封装。
-#![cfg(infini)]
 // #![deny(warnings, missing_docs)]
 
+#[cfg(not(any(infini, feature = "cpu", feature = "dlopen")))]
+compile_error!(
+    "InfiniCore library not found: set INFINI_ROOT to the InfiniCore install prefix \
+     (the directory containing include/ and lib/)"
+);
+
 /// 包含从 C 库生成的原始绑定的模块。
 #[macro_use]
 /// 规范命名
@@ -18,6 +23,8 @@ pub mod bindings {
     #[macro_export]
     macro_rules! infini {
         ($f:expr) => {{
+            // 第一次调用 InfiniCore 前按默认配置初始化运行时
+            $crate::Runtime::get();
             // 允许未使用的导入
             #[allow(unused_imports)]
             use $crate::bindings::*;
@@ -31,31 +38,81 @@ pub mod bindings {
 
 use bindings::{infiniDevice_t, infiniDtype_t};
 
-/// 初始化 InfiniCore 运行时环境
+mod dtype;
+mod error;
+
+pub use dtype::{UnsupportedDtype, UnsupportedLayout, layouts};
+pub use error::Error;
+
+mod runtime;
+
+pub use runtime::{Allocator, Config, InitError, Runtime, init_with};
+
+/// 按默认配置初始化 InfiniCore 运行时环境，参见 [`Runtime::get`]。
+///
+/// 运行时只初始化一次，重复调用没有作用。其他 API 在第一次调用 InfiniCore 时也会自动初始化；
+/// 需要自定义配置或处理初始化错误时使用 [`init_with`]。
 #[inline]
 pub fn init() {
-    infini!(infinirtInit());
+    Runtime::get();
 }
 
+/// 动态加载
+#[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+mod dl;
+
+#[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+pub use dl::{LoadError, LoadOptions, is_loaded, load};
+
 /// infinirt
+#[cfg(feature = "cpu")]
+mod cpu;
 mod device;
 mod event;
 mod memory;
 mod stream;
+mod workspace;
 
-pub use device::{Device, DeviceType};
+pub use device::{Device, DeviceType, available_backends};
 pub use event::Event;
 pub use memory::{DevBlob, DevByte, HostBlob};
 pub use stream::Stream;
+pub use workspace::Workspace;
+
+/// infiniccl
+#[cfg(feature = "ccl")]
+mod ccl;
+
+#[cfg(feature = "ccl")]
+pub use ccl::{Communicator, ReduceOp};
 
 /// infiniop
+mod cache;
 mod descriptor;
 mod handle;
+mod operator;
 mod tensor;
 
+pub mod ops;
+
+pub use cache::CacheStats;
 pub use descriptor::Descriptor;
 pub use handle::Handle;
-pub use tensor::Tensor;
+pub use operator::Operator;
+pub use tensor::{DevTensor, RearrangeRequired, Tensor};
+
+/// 模型文件
+#[cfg(feature = "safetensors")]
+pub mod safetensors;
+
+#[cfg(feature = "safetensors")]
+pub use safetensors::{Safetensors, SafetensorsError};
+
+#[cfg(feature = "gguf")]
+pub mod gguf;
+
+#[cfg(feature = "gguf")]
+pub use gguf::{Gguf, GgufError};
 
 /// 资源的原始形式的表示。通常来自底层库的定义。
 pub trait AsRaw {
@@ -1,12 +1,12 @@
-use crate::{AsRaw, Device, Stream};
+use crate::{Allocator, AsRaw, Device, Runtime, Stream};
 use std::{
-    sync::Arc,
     alloc::Layout,
-    mem::forget,
+    mem::ManuallyDrop,
     ops::{Deref, DerefMut},
     os::raw::c_void,
-    ptr::{null_mut, NonNull},
+    ptr::{self, NonNull, null_mut},
     slice::{from_raw_parts, from_raw_parts_mut},
+    sync::Arc,
 };
 
 /// 一个标记类型，表示设备内存中的一个字节。
@@ -128,21 +128,48 @@ pub struct DevBlob {
     ptr: Arc<NonNull<DevByte>>,
     offset: usize,
     nbytes: usize,
+    device: Device,
 }
 
 impl DevBlob {
+    /// 构造一个共享同一块设备内存的子区间 `[offset, offset + nbytes)`。
+    ///
+    /// # Panics
+    ///
+    /// 如果子区间超出当前 Blob 的范围。
     pub fn slice(&self, offset: usize, nbytes: usize) -> Self {
+        assert!(offset + nbytes <= self.nbytes, "blob slice out of range");
         DevBlob {
             ptr: self.ptr.clone(),
-            offset: offset,
-            nbytes: nbytes,
+            offset: self.offset + offset,
+            nbytes,
+            device: self.device,
         }
     }
+
+    /// 这块内存所在的设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// 底层是否有 infinirt 分配的内存，空分配使用悬垂指针，不需要释放。
+    ///
+    /// 与视图的长度无关：长度为 0 的子区间仍然可能持有一块真实分配的最后一个引用。
+    #[inline]
+    fn is_allocated(&self) -> bool {
+        *self.ptr != NonNull::dangling()
+    }
+
+    #[inline]
+    fn data(&self) -> *mut DevByte {
+        unsafe { self.ptr.as_ptr().add(self.offset) }
+    }
 }
 
 impl Drop for DevBlob {
     fn drop(&mut self) {
-        if self.nbytes == 0 {
+        if !self.is_allocated() {
             return;
         }
         if Arc::strong_count(&self.ptr) == 1 {
@@ -151,13 +178,13 @@ impl Drop for DevBlob {
     }
 }
 
-
 impl Device {
-    /// 在设备上同步分配指定类型的内存。
+    /// 激活此设备并在其上同步分配 `nbytes` 字节的内存。
     pub fn malloc(&self, nbytes: usize) -> DevBlob {
         let ptr = if nbytes == 0 {
             NonNull::dangling()
         } else {
+            self.set_device();
             let mut ptr = null_mut();
             infini!(infinirtMalloc(&mut ptr, nbytes));
             NonNull::new(ptr).unwrap().cast()
@@ -167,16 +194,18 @@ impl Device {
             ptr: Arc::new(ptr),
             offset: 0,
             nbytes: nbytes,
+            device: *self,
         }
     }
 
-    /// 从主机内存数据同步创建设备内存 Blob 并复制内容。
+    /// 激活此设备，从主机内存数据同步创建设备内存 Blob 并复制内容。
     pub fn from_host<T: Copy>(&self, data: &[T]) -> DevBlob {
         let src = data.as_ptr().cast();
         let nbytes = size_of_val(data);
         let ptr = if nbytes == 0 {
             NonNull::dangling()
         } else {
+            self.set_device();
             let mut ptr = null_mut();
             infini!(infinirtMalloc(&mut ptr, nbytes));
             infini!(infinirtMemcpy(
@@ -192,6 +221,7 @@ impl Device {
             ptr: Arc::new(ptr),
             offset: 0,
             nbytes: nbytes,
+            device: *self,
         }
     }
 }
@@ -199,20 +229,19 @@ impl Device {
 impl Stream {
     /// 在设备上异步分配指定类型的内存。
     ///
-    /// 分配操作将在指定的流上排队。
+    /// 分配操作将在指定的流上排队；运行时配置为 [`Allocator::Sync`] 时同步分配。
     pub fn malloc(&self, nbytes: usize) -> DevBlob {
         let ptr = if nbytes == 0 {
             NonNull::dangling()
         } else {
-            let mut ptr = null_mut();
-            infini!(infinirtMallocAsync(&mut ptr, nbytes, self.as_raw()));
-            NonNull::new(ptr).unwrap().cast()
+            NonNull::new(self.alloc(nbytes)).unwrap().cast()
         };
 
         DevBlob {
             ptr: Arc::new(ptr),
             offset: 0,
             nbytes: nbytes,
+            device: self.device(),
         }
     }
 
@@ -225,15 +254,13 @@ impl Stream {
         let ptr = if nbytes == 0 {
             NonNull::dangling()
         } else {
-            let raw = unsafe { self.as_raw() };
-            let mut ptr = null_mut();
-            infini!(infinirtMallocAsync(&mut ptr, nbytes, raw));
+            let ptr = self.alloc(nbytes);
             infini!(infinirtMemcpyAsync(
                 ptr,
                 src,
                 nbytes,
                 infinirtMemcpyKind_t::INFINIRT_MEMCPY_H2D,
-                raw
+                self.as_raw()
             ));
             NonNull::new(ptr).unwrap().cast()
         };
@@ -242,25 +269,47 @@ impl Stream {
             ptr: Arc::new(ptr),
             offset: 0,
             nbytes: nbytes,
+            device: self.device(),
         }
     }
 
     /// 在指定的流上异步释放设备内存 Blob。
+    ///
+    /// 只有 `blob` 是这块内存的最后一个引用时才会释放，否则只减少引用计数，
+    /// 由最后一个引用在析构时同步释放。
     pub fn free(&self, blob: DevBlob) {
-        if blob.nbytes == 0 {
+        if !blob.is_allocated() {
             return;
         }
 
-        let ptr = blob.ptr.clone();
-        forget(blob);
+        // 取出 Arc 而不运行 DevBlob 的析构，避免同步释放
+        let blob = ManuallyDrop::new(blob);
+        let ptr = unsafe { ptr::read(&blob.ptr) };
+
+        if let Ok(ptr) = Arc::try_unwrap(ptr) {
+            match Runtime::get().allocator() {
+                Allocator::Stream => {
+                    infini!(infinirtFreeAsync(ptr.as_ptr().cast(), self.as_raw()))
+                }
+                Allocator::Sync => {
+                    self.synchronize();
+                    infini!(infinirtFree(ptr.as_ptr().cast()))
+                }
+            }
+        }
+    }
 
-        if Arc::strong_count(&ptr) == 1 {
-            infini!(infinirtFreeAsync(ptr.as_ptr().cast(), self.as_raw()))
+    /// 按运行时配置的 [`Allocator`] 在流上分配 `nbytes` 字节。
+    fn alloc(&self, nbytes: usize) -> *mut c_void {
+        let mut ptr = null_mut();
+        match Runtime::get().allocator() {
+            Allocator::Stream => infini!(infinirtMallocAsync(&mut ptr, nbytes, self.as_raw())),
+            Allocator::Sync => infini!(infinirtMalloc(&mut ptr, nbytes)),
         }
+        ptr
     }
 }
 
-
 unsafe impl Send for DevBlob {}
 unsafe impl Sync for DevBlob {}
 
@@ -268,7 +317,7 @@ impl AsRaw for DevBlob {
     type Raw = *mut DevByte;
     #[inline]
     unsafe fn as_raw(&self) -> Self::Raw {
-        self.ptr.as_ptr()
+        self.data()
     }
 }
 
@@ -279,7 +328,7 @@ impl Deref for DevBlob {
         if self.nbytes == 0 {
             &[]
         } else {
-            unsafe { from_raw_parts(self.ptr.as_ptr(), self.nbytes) }
+            unsafe { from_raw_parts(self.data(), self.nbytes) }
         }
     }
 }
@@ -290,7 +339,7 @@ impl DerefMut for DevBlob {
         if self.nbytes == 0 {
             &mut []
         } else {
-            unsafe { from_raw_parts_mut(self.ptr.as_ptr(), self.nbytes) }
+            unsafe { from_raw_parts_mut(self.data(), self.nbytes) }
         }
     }
 }
@@ -305,7 +354,7 @@ pub struct HostBlob {
 }
 
 impl Device {
-    /// 在主机上同步分配指定类型的“固定”（pinned）或“主机映射”（host-mapped）内存。
+    /// 激活此设备，在主机上同步分配指定类型的“固定”（pinned）或“主机映射”（host-mapped）内存。
     pub fn malloc_host<T: Copy>(&self, nbytes: usize) -> HostBlob {
         let layout = Layout::array::<T>(nbytes).unwrap();
         let nbytes = layout.size();
@@ -314,6 +363,7 @@ impl Device {
             ptr: if nbytes == 0 {
                 NonNull::dangling()
             } else {
+                self.set_device();
                 let mut ptr = null_mut();
                 infini!(infinirtMallocHost(&mut ptr, nbytes));
                 NonNull::new(ptr).unwrap().cast()
@@ -0,0 +1,161 @@
+use crate::{Descriptor, DevByte, Stream, bindings::InfiniopDescriptor};
+
+/// 一个 infiniop 算子。
+///
+/// 每个算子的生命周期都是：用 [`Handle`](crate::Handle) 和张量描述符创建描述符，
+/// 查询启动所需的工作空间大小，然后在 [`Stream`](crate::Stream) 上启动。
+/// 通常用 [`operator!`](crate::operator) 宏从 C 函数名声明算子类型。
+pub trait Operator {
+    /// 算子名称。
+    const NAME: &'static str;
+
+    /// 底层算子描述符。
+    fn descriptor(&self) -> &Descriptor<InfiniopDescriptor>;
+
+    /// 启动算子所需的工作空间字节数。
+    fn workspace_size(&self) -> usize;
+}
+
+/// 从 `stream` 的 [`Workspace`](crate::Workspace) 中为 `op` 取出工作空间并执行 `f`。
+pub(crate) fn with_workspace<T>(
+    op: &impl Operator,
+    stream: &Stream,
+    f: impl FnOnce(&mut [DevByte]) -> T,
+) -> T {
+    f(stream.workspace().get(op.workspace_size()))
+}
+
+/// 从 C 函数名声明一个 infiniop 算子。
+///
+/// 适用于遵循 infiniop 通用签名的算子：
+///
+/// * `create(handle, &mut desc, outputs..., inputs..., create_args...)`；
+/// * `workspace(desc, &mut size)`；
+/// * `launch(desc, workspace, workspace_size, outputs..., inputs..., launch_args..., stream)`；
+/// * `destroy(desc)`。
+///
+/// 生成的类型提供 `new` 和 `unsafe fn launch`，并实现 [`Operator`](crate::Operator)。
+/// 可选的 `check` 是一个 `fn(&[&Tensor]) -> Result<(), Error>`，
+/// 在调用 C 库创建描述符之前按“输出在前、输入在后”的顺序接收张量描述符进行校验。
+///
+/// ```ignore
+/// operator! {
+///     /// 逐元素加法 `c = a + b`。
+///     pub struct Add {
+///         create: infiniopCreateAddDescriptor,
+///         workspace: infiniopGetAddWorkspaceSize,
+///         launch: infiniopAdd,
+///         destroy: infiniopDestroyAddDescriptor,
+///         outputs: [c],
+///         inputs: [a, b],
+///         create_args: [],
+///         launch_args: [],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! operator {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            create: $create:ident,
+            workspace: $workspace:ident,
+            launch: $launch:ident,
+            destroy: $destroy:ident,
+            $(check: $check:path,)?
+            outputs: [$($out:ident),* $(,)?],
+            inputs: [$($in:ident),* $(,)?],
+            create_args: [$($carg:ident: $cty:ty),* $(,)?],
+            launch_args: [$($larg:ident: $lty:ty),* $(,)?] $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            desc: $crate::Descriptor<$crate::bindings::InfiniopDescriptor>,
+            workspace_size: usize,
+        }
+
+        impl $name {
+            /// 创建算子描述符并查询工作空间大小。
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(
+                handle: &$crate::Handle,
+                $($out: &$crate::Tensor,)*
+                $($in: &$crate::Tensor,)*
+                $($carg: $cty,)*
+            ) -> Result<Self, $crate::Error> {
+                use $crate::AsRaw;
+                let _tensors: &[&$crate::Tensor] = &[$($out,)* $($in,)*];
+                $($check(_tensors)?;)?
+                let desc = $crate::Descriptor::try_new(
+                    |ptr| unsafe {
+                        $crate::bindings::$create(
+                            handle.as_raw(),
+                            ptr,
+                            $($out.as_raw(),)*
+                            $($in.as_raw(),)*
+                            $($carg,)*
+                        )
+                    },
+                    $crate::bindings::$destroy,
+                )?;
+                let mut workspace_size = 0;
+                $crate::Error::check(unsafe {
+                    $crate::bindings::$workspace(desc.as_raw(), &mut workspace_size)
+                })?;
+                Ok(Self {
+                    desc,
+                    workspace_size,
+                })
+            }
+
+            /// 在 `stream` 上启动算子。
+            ///
+            /// # Safety
+            ///
+            /// 各指针必须指向与创建时描述符相符的设备内存，
+            /// 且在流上的计算完成之前保持有效。
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn launch(
+                &self,
+                workspace: &mut [$crate::DevByte],
+                $($out: *mut $crate::DevByte,)*
+                $($in: *const $crate::DevByte,)*
+                $($larg: $lty,)*
+                stream: &$crate::Stream,
+            ) -> Result<(), $crate::Error> {
+                use $crate::AsRaw;
+                if workspace.len() < self.workspace_size {
+                    return Err($crate::Error::Status(
+                        $crate::bindings::infiniStatus_t::INFINI_STATUS_INSUFFICIENT_WORKSPACE,
+                    ));
+                }
+                $crate::Error::check(unsafe {
+                    $crate::bindings::$launch(
+                        self.desc.as_raw(),
+                        workspace.as_mut_ptr().cast(),
+                        workspace.len(),
+                        $($out.cast(),)*
+                        $($in.cast(),)*
+                        $($larg,)*
+                        stream.as_raw(),
+                    )
+                })
+            }
+        }
+
+        impl $crate::Operator for $name {
+            const NAME: &'static str = stringify!($name);
+
+            #[inline]
+            fn descriptor(&self) -> &$crate::Descriptor<$crate::bindings::InfiniopDescriptor> {
+                &self.desc
+            }
+
+            #[inline]
+            fn workspace_size(&self) -> usize {
+                self.workspace_size
+            }
+        }
+    };
+}
@@ -0,0 +1,407 @@
+use crate::{
+    AsRaw, Descriptor, DevByte, DevTensor, Error, Handle, Operator, Stream, Tensor,
+    bindings::{InfiniopDescriptor, infiniStatus_t},
+    operator,
+    operator::with_workspace,
+};
+
+operator! {
+    /// 因果掩码 softmax，沿最后一维计算。
+    ///
+    /// `x`、`y` 的形状为 `[seq_len, total_len]` 或 `[batch, seq_len, total_len]`，
+    /// 其中 `total_len = past_len + seq_len`，第 `i` 行只保留前 `past_len + i + 1` 个元素。
+    /// `y` 可以与 `x` 是同一块内存。
+    pub struct CausalSoftmax {
+        create: infiniopCreateCausalSoftmaxDescriptor,
+        workspace: infiniopGetCausalSoftmaxWorkspaceSize,
+        launch: infiniopCausalSoftmax,
+        destroy: infiniopDestroyCausalSoftmaxDescriptor,
+        check: check_causal_softmax,
+        outputs: [y],
+        inputs: [x],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+fn check_causal_softmax(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[y, x] = tensors else { unreachable!() };
+    if y.dt() != x.dt() {
+        return Err(Error::BadDtype(format!(
+            "causal softmax output {} differs from input {}",
+            y.dt(),
+            x.dt(),
+        )));
+    }
+    let (&[.., seq_len, total_len], 2..=3) = (x.shape(), x.ndim()) else {
+        return Err(Error::BadShape(format!(
+            "causal softmax x must be 2-D or 3-D, got {:?}",
+            x.shape(),
+        )));
+    };
+    if y.shape() != x.shape() || total_len < seq_len {
+        return Err(Error::BadShape(format!(
+            "causal softmax requires y and x of the same shape with total_len >= seq_len, got y {:?}, x {:?}",
+            y.shape(),
+            x.shape(),
+        )));
+    }
+    Ok(())
+}
+
+/// 带 KV 缓存的融合注意力。
+///
+/// 张量形状如下，其中 `n_head` 必须是 `n_kv_head` 的整数倍（分组查询注意力）：
+///
+/// * `q`：`[n_head, seq_len, head_dim]`；
+/// * `k`、`v`：`[n_kv_head, seq_len, head_dim]`；
+/// * `k_cache`、`v_cache`：`[n_kv_head, max_len, head_dim]`，`max_len >= pos + seq_len`；
+/// * `out`：`[seq_len, n_head, head_dim]`。
+///
+/// 启动时先将本轮的 `k`、`v` 写入缓存的 `[pos, pos + seq_len)` 位置，
+/// 再以缓存中前 `pos + seq_len` 个位置计算因果注意力。
+pub struct Attention {
+    desc: Descriptor<InfiniopDescriptor>,
+    workspace_size: usize,
+}
+
+impl Attention {
+    /// 创建算子描述符并查询工作空间大小，`pos` 是缓存中已有的历史长度。
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        handle: &Handle,
+        out: &Tensor,
+        q: &Tensor,
+        k: &Tensor,
+        v: &Tensor,
+        k_cache: &Tensor,
+        v_cache: &Tensor,
+        pos: usize,
+    ) -> Result<Self, Error> {
+        check_attention(out, q, k, v, k_cache, v_cache, pos)?;
+        let desc = Descriptor::try_new(
+            |ptr| unsafe {
+                crate::bindings::infiniopCreateAttentionDescriptor(
+                    handle.as_raw(),
+                    ptr,
+                    out.as_raw(),
+                    q.as_raw(),
+                    k.as_raw(),
+                    v.as_raw(),
+                    k_cache.as_raw(),
+                    v_cache.as_raw(),
+                    pos,
+                )
+            },
+            crate::bindings::infiniopDestroyAttentionDescriptor,
+        )?;
+        let mut workspace_size = 0;
+        Error::check(unsafe {
+            crate::bindings::infiniopGetAttentionWorkspaceSize(desc.as_raw(), &mut workspace_size)
+        })?;
+        Ok(Self {
+            desc,
+            workspace_size,
+        })
+    }
+
+    /// 在 `stream` 上启动算子。
+    ///
+    /// # Safety
+    ///
+    /// 各指针必须指向与创建时描述符相符的设备内存，
+    /// 且在流上的计算完成之前保持有效。
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch(
+        &self,
+        workspace: &mut [DevByte],
+        out: *mut DevByte,
+        q: *const DevByte,
+        k: *const DevByte,
+        v: *const DevByte,
+        k_cache: *mut DevByte,
+        v_cache: *mut DevByte,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        if workspace.len() < self.workspace_size {
+            return Err(Error::Status(
+                infiniStatus_t::INFINI_STATUS_INSUFFICIENT_WORKSPACE,
+            ));
+        }
+        Error::check(unsafe {
+            crate::bindings::infiniopAttention(
+                self.desc.as_raw(),
+                workspace.as_mut_ptr().cast(),
+                workspace.len(),
+                out.cast(),
+                q.cast(),
+                k.cast(),
+                v.cast(),
+                k_cache.cast(),
+                v_cache.cast(),
+                stream.as_raw(),
+            )
+        })
+    }
+}
+
+impl Operator for Attention {
+    const NAME: &'static str = "Attention";
+
+    #[inline]
+    fn descriptor(&self) -> &Descriptor<InfiniopDescriptor> {
+        &self.desc
+    }
+
+    #[inline]
+    fn workspace_size(&self) -> usize {
+        self.workspace_size
+    }
+}
+
+fn check_attention(
+    out: &Tensor,
+    q: &Tensor,
+    k: &Tensor,
+    v: &Tensor,
+    k_cache: &Tensor,
+    v_cache: &Tensor,
+    pos: usize,
+) -> Result<(), Error> {
+    let tensors = [
+        ("out", out),
+        ("q", q),
+        ("k", k),
+        ("v", v),
+        ("k_cache", k_cache),
+        ("v_cache", v_cache),
+    ];
+    for (name, t) in tensors {
+        if t.dt() != q.dt() {
+            return Err(Error::BadDtype(format!(
+                "attention {name} is {}, expected {}",
+                t.dt(),
+                q.dt(),
+            )));
+        }
+        if t.ndim() != 3 {
+            return Err(Error::BadShape(format!(
+                "attention {name} must be 3-D, got {:?}",
+                t.shape(),
+            )));
+        }
+    }
+
+    let &[n_head, seq_len, dh] = q.shape() else {
+        unreachable!()
+    };
+    let n_kv_head = k.shape()[0];
+    if n_kv_head == 0 || n_head % n_kv_head != 0 {
+        return Err(Error::BadShape(format!(
+            "attention n_head {n_head} is not a multiple of n_kv_head {n_kv_head}",
+        )));
+    }
+    let kv = [n_kv_head, seq_len, dh];
+    if k.shape() != kv || v.shape() != kv {
+        return Err(Error::BadShape(format!(
+            "attention k and v must be {kv:?}, got k {:?}, v {:?}",
+            k.shape(),
+            v.shape(),
+        )));
+    }
+    for (name, t) in [("k_cache", k_cache), ("v_cache", v_cache)] {
+        let &[h, max_len, d] = t.shape() else {
+            unreachable!()
+        };
+        if h != n_kv_head || d != dh || max_len < pos + seq_len {
+            return Err(Error::BadShape(format!(
+                "attention {name} must be [{n_kv_head}, >= {}, {dh}], got {:?}",
+                pos + seq_len,
+                t.shape(),
+            )));
+        }
+    }
+    if out.shape() != [seq_len, n_head, dh] {
+        return Err(Error::BadShape(format!(
+            "attention out must be [{seq_len}, {n_head}, {dh}], got {:?}",
+            out.shape(),
+        )));
+    }
+    Ok(())
+}
+
+impl Handle {
+    /// 在 `stream` 上对 `att` 原地计算因果掩码 softmax，参见 [`CausalSoftmax`]。
+    pub fn causal_softmax(&self, att: &mut DevTensor, stream: &Stream) -> Result<(), Error> {
+        let op = self.prepare(stream, &[att, att], &[], || {
+            CausalSoftmax::new(self, att, att)
+        })?;
+        let ptr = att.as_mut_ptr();
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(workspace, ptr, ptr, stream)
+        })
+    }
+
+    /// 在 `stream` 上计算带 KV 缓存的融合注意力，参见 [`Attention`]。
+    ///
+    /// `pos` 是缓存中已有的历史长度，本轮长度由 `q` 的形状给出；
+    /// 启动后 `k_cache`、`v_cache` 的 `[pos, pos + seq_len)` 位置被写入本轮的 `k`、`v`。
+    ///
+    /// infiniop 在创建描述符时固定 `pos`，逐词解码时每一步的 `pos` 都不同，
+    /// 因此这里每次调用都创建新的描述符，不经过算子缓存，以免挤出其他算子的缓存项。
+    /// 描述符在启动后即销毁，启动时参数已经提交到流上。
+    #[allow(clippy::too_many_arguments)]
+    pub fn attention(
+        &self,
+        out: &mut DevTensor,
+        q: &DevTensor,
+        k: &DevTensor,
+        v: &DevTensor,
+        k_cache: &mut DevTensor,
+        v_cache: &mut DevTensor,
+        pos: usize,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        self.check_devices(stream, &[out, q, k, v, k_cache, v_cache])?;
+        let op = Attention::new(self, out, q, k, v, k_cache, v_cache, pos)?;
+        with_workspace(&op, stream, |workspace| unsafe {
+            op.launch(
+                workspace,
+                out.as_mut_ptr(),
+                q.as_ptr(),
+                k.as_ptr(),
+                v.as_ptr(),
+                k_cache.as_mut_ptr(),
+                v_cache.as_mut_ptr(),
+                stream,
+            )
+        })
+    }
+}
+
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod tests {
+    use crate::ops::test_utils::{assert_close, cpu, data, download, upload};
+    use digit_layout::types;
+
+    /// 按行计算 softmax，每行只保留前 `valid(i)` 个元素，其余为 0。
+    fn masked_softmax(x: &mut [f32], width: usize, valid: impl Fn(usize) -> usize) {
+        for (i, row) in x.chunks_mut(width).enumerate() {
+            let (keep, masked) = row.split_at_mut(valid(i));
+            let max = keep.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            keep.iter_mut().for_each(|x| *x = (*x - max).exp());
+            let sum = keep.iter().sum::<f32>();
+            keep.iter_mut().for_each(|x| *x /= sum);
+            masked.fill(0.)
+        }
+    }
+
+    #[test]
+    fn causal_softmax() {
+        const BATCH: usize = 2;
+        const SEQ: usize = 3;
+        const TOTAL: usize = 5;
+        let (handle, stream) = cpu();
+        let host = data(BATCH * SEQ * TOTAL, 11);
+        let mut att = upload(&stream, types::F32, &[BATCH, SEQ, TOTAL], &host);
+        handle.causal_softmax(&mut att, &stream).unwrap();
+
+        let mut expected = host;
+        masked_softmax(&mut expected, TOTAL, |i| TOTAL - SEQ + i % SEQ + 1);
+        assert_close(&download(&stream, &att), &expected, 1e-6, 1e-5);
+    }
+
+    #[test]
+    fn attention_decode() {
+        const NH: usize = 4;
+        const NKVH: usize = 2;
+        const DH: usize = 8;
+        const MAX_LEN: usize = 8;
+        let (handle, stream) = cpu();
+        let cache_shape = [NKVH, MAX_LEN, DH];
+        let mut k_cache = upload(
+            &stream,
+            types::F32,
+            &cache_shape,
+            &[0.; NKVH * MAX_LEN * DH],
+        );
+        let mut v_cache = upload(
+            &stream,
+            types::F32,
+            &cache_shape,
+            &[0.; NKVH * MAX_LEN * DH],
+        );
+        let mut k_host = vec![0.; NKVH * MAX_LEN * DH];
+        let mut v_host = vec![0.; NKVH * MAX_LEN * DH];
+
+        // 先预填 3 个位置，再逐词解码 2 步
+        let mut pos = 0;
+        for (step, seq) in [3, 1, 1].into_iter().enumerate() {
+            let seed = step as u32 * 3;
+            let q_host = data(NH * seq * DH, seed + 1);
+            let k_new = data(NKVH * seq * DH, seed + 2);
+            let v_new = data(NKVH * seq * DH, seed + 3);
+            let q = upload(&stream, types::F32, &[NH, seq, DH], &q_host);
+            let k = upload(&stream, types::F32, &[NKVH, seq, DH], &k_new);
+            let v = upload(&stream, types::F32, &[NKVH, seq, DH], &v_new);
+            let mut out = upload(
+                &stream,
+                types::F32,
+                &[seq, NH, DH],
+                &vec![0.; seq * NH * DH],
+            );
+            handle
+                .attention(
+                    &mut out,
+                    &q,
+                    &k,
+                    &v,
+                    &mut k_cache,
+                    &mut v_cache,
+                    pos,
+                    &stream,
+                )
+                .unwrap();
+
+            for h in 0..NKVH {
+                for s in 0..seq {
+                    let dst = (h * MAX_LEN + pos + s) * DH;
+                    let src = (h * seq + s) * DH;
+                    k_host[dst..][..DH].copy_from_slice(&k_new[src..][..DH]);
+                    v_host[dst..][..DH].copy_from_slice(&v_new[src..][..DH]);
+                }
+            }
+            let total = pos + seq;
+            let mut expected = vec![0.; seq * NH * DH];
+            for h in 0..NH {
+                let kv = h / (NH / NKVH);
+                let mut att = vec![0.; seq * total];
+                for s in 0..seq {
+                    let q = &q_host[(h * seq + s) * DH..][..DH];
+                    for t in 0..total {
+                        let k = &k_host[(kv * MAX_LEN + t) * DH..][..DH];
+                        let dot = q.iter().zip(k).map(|(a, b)| a * b).sum::<f32>();
+                        att[s * total + t] = dot / (DH as f32).sqrt();
+                    }
+                }
+                masked_softmax(&mut att, total, |s| pos + s + 1);
+                for s in 0..seq {
+                    let out = &mut expected[(s * NH + h) * DH..][..DH];
+                    for t in 0..total {
+                        let v = &v_host[(kv * MAX_LEN + t) * DH..][..DH];
+                        for (o, v) in out.iter_mut().zip(v) {
+                            *o += att[s * total + t] * v
+                        }
+                    }
+                }
+            }
+            assert_close(&download(&stream, &out), &expected, 1e-5, 1e-4);
+            assert_close(&download(&stream, &k_cache), &k_host, 0., 0.);
+            assert_close(&download(&stream, &v_cache), &v_host, 0., 0.);
+            pos = total
+        }
+
+        // 注意力不经过算子缓存
+        assert_eq!(handle.cache_stats().len, 0);
+    }
+}
@@ -0,0 +1,614 @@
+use crate::{
+    AsRaw, Descriptor, DevByte, DevTensor, Error, Handle, Operator, Stream, Tensor,
+    bindings::{
+        InfiniopDescriptor, infiniStatus_t, infiniopCreateAvgPoolDescriptor,
+        infiniopCreateMaxPoolDescriptor, infiniopDestroyAvgPoolDescriptor,
+        infiniopDestroyMaxPoolDescriptor, infiniopGetAvgPoolWorkspaceSize,
+        infiniopGetMaxPoolWorkspaceSize,
+    },
+    operator::with_workspace,
+};
+use std::ptr::{null, null_mut};
+
+/// 卷积参数，各向量的长度等于空间维数（1 至 3）。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConvArgs {
+    /// 每个空间维两侧的填充。
+    pub pads: Vec<usize>,
+    /// 每个空间维的步长。
+    pub strides: Vec<usize>,
+    /// 每个空间维的空洞率。
+    pub dilations: Vec<usize>,
+    /// 分组数，输入通道数必须等于权重第 1 维乘以分组数。
+    ///
+    /// infiniop 的卷积描述符不接受分组数，[`Conv`] 目前只支持 1。
+    pub groups: usize,
+}
+
+impl ConvArgs {
+    /// `n` 个空间维上无填充、步长和空洞率均为 1、不分组的参数。
+    pub fn new(n: usize) -> Self {
+        Self {
+            pads: vec![0; n],
+            strides: vec![1; n],
+            dilations: vec![1; n],
+            groups: 1,
+        }
+    }
+
+    /// 由输入形状 `[n, c_in, *spatial]` 和权重形状 `[c_out, c_in / groups, *kernel]`
+    /// 推导输出形状 `[n, c_out, *spatial']`。
+    pub fn output_shape(&self, x: &[usize], w: &[usize]) -> Result<Vec<usize>, Error> {
+        let n = self.pads.len();
+        if !(1..=3).contains(&n) || self.strides.len() != n || self.dilations.len() != n {
+            return Err(Error::BadShape(format!(
+                "conv args must share 1 to 3 spatial dims, got {self:?}"
+            )));
+        }
+        if x.len() != n + 2 || w.len() != n + 2 {
+            return Err(Error::BadShape(format!(
+                "conv x and w must be {}-D, got x {x:?}, w {w:?}",
+                n + 2,
+            )));
+        }
+        if self.groups == 0 || x[1] != w[1] * self.groups || !w[0].is_multiple_of(self.groups) {
+            return Err(Error::BadShape(format!(
+                "conv channels do not match {} groups, got x {x:?}, w {w:?}",
+                self.groups,
+            )));
+        }
+        let mut y = vec![x[0], w[0]];
+        for i in 0..n {
+            let bad = || {
+                Error::BadShape(format!(
+                    "conv kernel {w:?} does not fit input {x:?} with {self:?}",
+                ))
+            };
+            if self.strides[i] == 0 || self.dilations[i] == 0 || w[i + 2] == 0 {
+                return Err(bad());
+            }
+            let input = self.pads[i]
+                .checked_mul(2)
+                .and_then(|p| p.checked_add(x[i + 2]))
+                .ok_or_else(bad)?;
+            let kernel = self.dilations[i]
+                .checked_mul(w[i + 2] - 1)
+                .and_then(|k| k.checked_add(1))
+                .ok_or_else(bad)?;
+            if input < kernel {
+                return Err(bad());
+            }
+            y.push((input - kernel) / self.strides[i] + 1);
+        }
+        Ok(y)
+    }
+}
+
+/// 池化参数，各向量的长度等于空间维数（1 至 3）。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolArgs {
+    /// 每个空间维的窗口大小。
+    pub kernel: Vec<usize>,
+    /// 每个空间维两侧的填充。
+    pub pads: Vec<usize>,
+    /// 每个空间维的步长。
+    pub strides: Vec<usize>,
+}
+
+impl PoolArgs {
+    /// 无填充、步长等于窗口大小的参数。
+    pub fn new(kernel: Vec<usize>) -> Self {
+        Self {
+            pads: vec![0; kernel.len()],
+            strides: kernel.clone(),
+            kernel,
+        }
+    }
+
+    /// 由输入形状 `[n, c, *spatial]` 推导输出形状 `[n, c, *spatial']`。
+    pub fn output_shape(&self, x: &[usize]) -> Result<Vec<usize>, Error> {
+        let n = self.kernel.len();
+        if !(1..=3).contains(&n) || self.pads.len() != n || self.strides.len() != n {
+            return Err(Error::BadShape(format!(
+                "pool args must share 1 to 3 spatial dims, got {self:?}"
+            )));
+        }
+        if x.len() != n + 2 {
+            return Err(Error::BadShape(format!(
+                "pool x must be {}-D, got {x:?}",
+                n + 2
+            )));
+        }
+        let mut y = vec![x[0], x[1]];
+        for i in 0..n {
+            let bad = || {
+                Error::BadShape(format!(
+                    "pool window does not fit input {x:?} with {self:?}",
+                ))
+            };
+            let input = self.pads[i]
+                .checked_mul(2)
+                .and_then(|p| p.checked_add(x[i + 2]))
+                .ok_or_else(bad)?;
+            let kernel = self.kernel[i];
+            if self.strides[i] == 0 || kernel == 0 || input < kernel {
+                return Err(bad());
+            }
+            y.push((input - kernel) / self.strides[i] + 1);
+        }
+        Ok(y)
+    }
+}
+
+/// 池化方式。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolKind {
+    /// 最大池化。
+    Max,
+    /// 平均池化。
+    Avg,
+}
+
+/// 1 至 3 维卷积，`b` 可选。
+pub struct Conv {
+    desc: Descriptor<InfiniopDescriptor>,
+    workspace_size: usize,
+}
+
+impl Conv {
+    /// 校验形状后创建算子描述符并查询工作空间大小。
+    ///
+    /// infiniop 不支持分组卷积，`args.groups` 不为 1 时返回 [`Error::BadShape`]。
+    pub fn new(
+        handle: &Handle,
+        y: &Tensor,
+        x: &Tensor,
+        w: &Tensor,
+        b: Option<&Tensor>,
+        args: &ConvArgs,
+    ) -> Result<Self, Error> {
+        if args.groups != 1 {
+            return Err(Error::BadShape(format!(
+                "grouped conv is not supported by infiniop, got {} groups",
+                args.groups,
+            )));
+        }
+        for t in [Some(y), Some(w), b].into_iter().flatten() {
+            if t.dt() != x.dt() {
+                return Err(Error::BadDtype(format!(
+                    "conv tensors must share dtype {}, got {}",
+                    x.dt(),
+                    t.dt(),
+                )));
+            }
+        }
+        let shape = args.output_shape(x.shape(), w.shape())?;
+        if y.shape() != shape {
+            return Err(Error::BadShape(format!(
+                "conv y must be {shape:?}, got {:?}",
+                y.shape(),
+            )));
+        }
+        if let Some(b) = b {
+            if b.shape() != [w.shape()[0]] {
+                return Err(Error::BadShape(format!(
+                    "conv b must be [{}], got {:?}",
+                    w.shape()[0],
+                    b.shape(),
+                )));
+            }
+        }
+
+        let mut pads = args.pads.clone();
+        let mut strides: Vec<isize> = args.strides.iter().map(|&s| s as _).collect();
+        let mut dilations = args.dilations.clone();
+        let desc = Descriptor::try_new(
+            |ptr| unsafe {
+                crate::bindings::infiniopCreateConvDescriptor(
+                    handle.as_raw(),
+                    ptr,
+                    y.as_raw(),
+                    x.as_raw(),
+                    w.as_raw(),
+                    b.map_or(null_mut(), |b| b.as_raw()),
+                    pads.as_mut_ptr().cast(),
+                    strides.as_mut_ptr().cast(),
+                    dilations.as_mut_ptr().cast(),
+                    pads.len(),
+                )
+            },
+            crate::bindings::infiniopDestroyConvDescriptor,
+        )?;
+        let mut workspace_size = 0;
+        Error::check(unsafe {
+            crate::bindings::infiniopGetConvWorkspaceSize(desc.as_raw(), &mut workspace_size)
+        })?;
+        Ok(Self {
+            desc,
+            workspace_size,
+        })
+    }
+
+    /// 在 `stream` 上启动算子，无偏置时 `b` 为空指针。
+    ///
+    /// # Safety
+    ///
+    /// 各指针必须指向与创建时描述符相符的设备内存，
+    /// 且在流上的计算完成之前保持有效。
+    pub unsafe fn launch(
+        &self,
+        workspace: &mut [DevByte],
+        y: *mut DevByte,
+        x: *const DevByte,
+        w: *const DevByte,
+        b: *const DevByte,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        if workspace.len() < self.workspace_size {
+            return Err(Error::Status(
+                infiniStatus_t::INFINI_STATUS_INSUFFICIENT_WORKSPACE,
+            ));
+        }
+        Error::check(unsafe {
+            crate::bindings::infiniopConv(
+                self.desc.as_raw(),
+                workspace.as_mut_ptr().cast(),
+                workspace.len(),
+                y.cast(),
+                x.cast(),
+                w.cast(),
+                b.cast(),
+                stream.as_raw(),
+            )
+        })
+    }
+}
+
+impl Operator for Conv {
+    const NAME: &'static str = "Conv";
+
+    #[inline]
+    fn descriptor(&self) -> &Descriptor<InfiniopDescriptor> {
+        &self.desc
+    }
+
+    #[inline]
+    fn workspace_size(&self) -> usize {
+        self.workspace_size
+    }
+}
+
+/// 1 至 3 维最大池化或平均池化。
+pub struct Pool {
+    kind: PoolKind,
+    desc: Descriptor<InfiniopDescriptor>,
+    workspace_size: usize,
+}
+
+impl Pool {
+    /// 校验形状后创建算子描述符并查询工作空间大小。
+    pub fn new(
+        handle: &Handle,
+        y: &Tensor,
+        x: &Tensor,
+        kind: PoolKind,
+        args: &PoolArgs,
+    ) -> Result<Self, Error> {
+        if y.dt() != x.dt() {
+            return Err(Error::BadDtype(format!(
+                "pool y {} differs from x {}",
+                y.dt(),
+                x.dt(),
+            )));
+        }
+        let shape = args.output_shape(x.shape())?;
+        if y.shape() != shape {
+            return Err(Error::BadShape(format!(
+                "pool y must be {shape:?}, got {:?}",
+                y.shape(),
+            )));
+        }
+
+        let strides: Vec<isize> = args.strides.iter().map(|&s| s as _).collect();
+        let n = args.kernel.len();
+        let (kernel, pads, strides) = (args.kernel.as_ptr(), args.pads.as_ptr(), strides.as_ptr());
+        let (h, y, x) = unsafe { (handle.as_raw(), y.as_raw(), x.as_raw()) };
+        let desc = match kind {
+            PoolKind::Max => Descriptor::try_new(
+                |ptr| unsafe {
+                    infiniopCreateMaxPoolDescriptor(h, ptr, y, x, kernel, pads, strides, n)
+                },
+                infiniopDestroyMaxPoolDescriptor,
+            )?,
+            PoolKind::Avg => Descriptor::try_new(
+                |ptr| unsafe {
+                    infiniopCreateAvgPoolDescriptor(h, ptr, y, x, kernel, pads, strides, n)
+                },
+                infiniopDestroyAvgPoolDescriptor,
+            )?,
+        };
+        let mut workspace_size = 0;
+        Error::check(unsafe {
+            match kind {
+                PoolKind::Max => {
+                    infiniopGetMaxPoolWorkspaceSize(desc.as_raw(), &mut workspace_size)
+                }
+                PoolKind::Avg => {
+                    infiniopGetAvgPoolWorkspaceSize(desc.as_raw(), &mut workspace_size)
+                }
+            }
+        })?;
+        Ok(Self {
+            kind,
+            desc,
+            workspace_size,
+        })
+    }
+
+    /// 池化方式。
+    #[inline]
+    pub fn kind(&self) -> PoolKind {
+        self.kind
+    }
+
+    /// 在 `stream` 上启动算子。
+    ///
+    /// # Safety
+    ///
+    /// 各指针必须指向与创建时描述符相符的设备内存，
+    /// 且在流上的计算完成之前保持有效。
+    pub unsafe fn launch(
+        &self,
+        workspace: &mut [DevByte],
+        y: *mut DevByte,
+        x: *const DevByte,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        if workspace.len() < self.workspace_size {
+            return Err(Error::Status(
+                infiniStatus_t::INFINI_STATUS_INSUFFICIENT_WORKSPACE,
+            ));
+        }
+        let launch = match self.kind {
+            PoolKind::Max => crate::bindings::infiniopMaxPool,
+            PoolKind::Avg => crate::bindings::infiniopAvgPool,
+        };
+        Error::check(unsafe {
+            launch(
+                self.desc.as_raw(),
+                workspace.as_mut_ptr().cast(),
+                workspace.len(),
+                y.cast(),
+                x.cast(),
+                stream.as_raw(),
+            )
+        })
+    }
+}
+
+impl Operator for Pool {
+    const NAME: &'static str = "Pool";
+
+    #[inline]
+    fn descriptor(&self) -> &Descriptor<InfiniopDescriptor> {
+        &self.desc
+    }
+
+    #[inline]
+    fn workspace_size(&self) -> usize {
+        self.workspace_size
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上计算卷积，参见 [`Conv`]。`y` 的形状可由 [`ConvArgs::output_shape`] 得到。
+    pub fn conv(
+        &self,
+        y: &mut DevTensor,
+        x: &DevTensor,
+        w: &DevTensor,
+        b: Option<&DevTensor>,
+        args: &ConvArgs,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let mut tensors = vec![&*y, x, w];
+        tensors.extend(b);
+        let key = [args.groups]
+            .iter()
+            .chain(&args.pads)
+            .chain(&args.strides)
+            .chain(&args.dilations)
+            .map(|&n| n as u64)
+            .collect::<Vec<_>>();
+        let op = self.prepare(stream, &tensors, &key, || {
+            Conv::new(self, y, x, w, b.map(|b| b.desc()), args)
+        })?;
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(
+                workspace,
+                y.as_mut_ptr(),
+                x.as_ptr(),
+                w.as_ptr(),
+                b.map_or(null(), |b| b.as_ptr()),
+                stream,
+            )
+        })
+    }
+
+    /// 在 `stream` 上计算池化，参见 [`Pool`]。`y` 的形状可由 [`PoolArgs::output_shape`] 得到。
+    pub fn pool(
+        &self,
+        y: &mut DevTensor,
+        x: &DevTensor,
+        kind: PoolKind,
+        args: &PoolArgs,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let key = [kind as usize]
+            .iter()
+            .chain(&args.kernel)
+            .chain(&args.pads)
+            .chain(&args.strides)
+            .map(|&n| n as u64)
+            .collect::<Vec<_>>();
+        let op = self.prepare(stream, &[y, x], &key, || Pool::new(self, y, x, kind, args))?;
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(workspace, y.as_mut_ptr(), x.as_ptr(), stream)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConvArgs, Error, PoolArgs};
+
+    #[test]
+    fn conv_output_shape() {
+        let args = ConvArgs::new(2);
+        assert_eq!(
+            args.output_shape(&[2, 3, 8, 6], &[4, 3, 3, 3]).unwrap(),
+            [2, 4, 6, 4],
+        );
+        let args = ConvArgs {
+            pads: vec![1, 0],
+            strides: vec![2, 1],
+            dilations: vec![1, 2],
+            groups: 1,
+        };
+        // 高：(8 + 2 - 3) / 2 + 1 = 4；宽：(6 - 5) / 1 + 1 = 2
+        assert_eq!(
+            args.output_shape(&[1, 3, 8, 6], &[4, 3, 3, 3]).unwrap(),
+            [1, 4, 4, 2],
+        );
+        let grouped = ConvArgs {
+            groups: 2,
+            ..ConvArgs::new(1)
+        };
+        assert_eq!(
+            grouped.output_shape(&[1, 4, 5], &[6, 2, 3]).unwrap(),
+            [1, 6, 3],
+        );
+    }
+
+    #[test]
+    fn conv_output_shape_rejects() {
+        let args = ConvArgs::new(1);
+        // 零长度的卷积核不能下溢
+        assert!(args.output_shape(&[1, 1, 4], &[1, 1, 0]).is_err());
+        // 卷积核大于输入
+        assert!(args.output_shape(&[1, 1, 2], &[1, 1, 3]).is_err());
+        // 通道数不匹配
+        assert!(args.output_shape(&[1, 2, 4], &[1, 1, 3]).is_err());
+        // 维数不匹配
+        assert!(args.output_shape(&[1, 1, 4, 4], &[1, 1, 3]).is_err());
+        for bad in [
+            ConvArgs {
+                strides: vec![0],
+                ..ConvArgs::new(1)
+            },
+            ConvArgs {
+                dilations: vec![0],
+                ..ConvArgs::new(1)
+            },
+            ConvArgs {
+                dilations: vec![usize::MAX],
+                ..ConvArgs::new(1)
+            },
+            ConvArgs {
+                pads: vec![usize::MAX],
+                ..ConvArgs::new(1)
+            },
+            ConvArgs {
+                groups: 0,
+                ..ConvArgs::new(1)
+            },
+            ConvArgs::new(4),
+        ] {
+            assert!(bad.output_shape(&[1, 1, 4], &[1, 1, 3]).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn pool_output_shape() {
+        let args = PoolArgs::new(vec![2, 2]);
+        assert_eq!(args.output_shape(&[1, 3, 8, 7]).unwrap(), [1, 3, 4, 3]);
+        assert!(PoolArgs::new(vec![0]).output_shape(&[1, 1, 4]).is_err());
+        assert!(PoolArgs::new(vec![5]).output_shape(&[1, 1, 4]).is_err());
+        // 填充后的输入长度溢出
+        let args = PoolArgs {
+            pads: vec![usize::MAX / 2],
+            ..PoolArgs::new(vec![2])
+        };
+        assert!(matches!(
+            args.output_shape(&[1, 1, 4]),
+            Err(Error::BadShape(_))
+        ));
+    }
+
+    #[cfg(all(infini_cpu, not(feature = "cpu")))]
+    #[test]
+    fn conv_matches_host() {
+        use crate::ops::test_utils::{assert_close, cpu, data, download, upload};
+        use digit_layout::types;
+
+        let (handle, stream) = cpu();
+        let args = ConvArgs {
+            pads: vec![1, 0],
+            strides: vec![2, 1],
+            dilations: vec![1, 2],
+            groups: 1,
+        };
+        let (xs, ws) = ([1, 3, 8, 6], [4, 3, 3, 3]);
+        let ys = args.output_shape(&xs, &ws).unwrap();
+        let x_host = data(xs.iter().product(), 21);
+        let w_host = data(ws.iter().product(), 22);
+        let b_host = data(ws[0], 23);
+        let x = upload(&stream, types::F32, &xs, &x_host);
+        let w = upload(&stream, types::F32, &ws, &w_host);
+        let b = upload(&stream, types::F32, &[ws[0]], &b_host);
+        let mut y = upload(&stream, types::F32, &ys, &vec![0.; ys.iter().product()]);
+        handle
+            .conv(&mut y, &x, &w, Some(&b), &args, &stream)
+            .unwrap();
+
+        let mut expected = Vec::new();
+        for co in 0..ys[1] {
+            for oh in 0..ys[2] {
+                for ow in 0..ys[3] {
+                    let mut acc = b_host[co];
+                    for ci in 0..xs[1] {
+                        for kh in 0..ws[2] {
+                            for kw in 0..ws[3] {
+                                let ih = (oh * args.strides[0] + kh * args.dilations[0]) as isize
+                                    - args.pads[0] as isize;
+                                let iw = (ow * args.strides[1] + kw * args.dilations[1]) as isize
+                                    - args.pads[1] as isize;
+                                if !(0..xs[2] as isize).contains(&ih)
+                                    || !(0..xs[3] as isize).contains(&iw)
+                                {
+                                    continue;
+                                }
+                                let x = x_host[(ci * xs[2] + ih as usize) * xs[3] + iw as usize];
+                                let w = w_host[((co * ws[1] + ci) * ws[2] + kh) * ws[3] + kw];
+                                acc += x * w
+                            }
+                        }
+                    }
+                    expected.push(acc)
+                }
+            }
+        }
+        assert_close(&download(&stream, &y), &expected, 1e-4, 1e-4);
+
+        let grouped = ConvArgs {
+            groups: 3,
+            ..ConvArgs::new(2)
+        };
+        let w = upload(&stream, types::F32, &[3, 1, 3, 3], &w_host[..27]);
+        assert!(
+            handle
+                .conv(&mut y, &x, &w, None, &grouped, &stream)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use super::upload_floats;
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+
+operator! {
+    /// 逐元素加法 `c = a + b`。
+    pub struct Add {
+        create: infiniopCreateAddDescriptor,
+        workspace: infiniopGetAddWorkspaceSize,
+        launch: infiniopAdd,
+        destroy: infiniopDestroyAddDescriptor,
+        check: check,
+        outputs: [c],
+        inputs: [a, b],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素减法 `c = a - b`。
+    pub struct Sub {
+        create: infiniopCreateSubDescriptor,
+        workspace: infiniopGetSubWorkspaceSize,
+        launch: infiniopSub,
+        destroy: infiniopDestroySubDescriptor,
+        check: check,
+        outputs: [c],
+        inputs: [a, b],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素乘法 `c = a * b`。
+    pub struct Mul {
+        create: infiniopCreateMulDescriptor,
+        workspace: infiniopGetMulWorkspaceSize,
+        launch: infiniopMul,
+        destroy: infiniopDestroyMulDescriptor,
+        check: check,
+        outputs: [c],
+        inputs: [a, b],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// SwiGLU 门控激活 `c = a * silu(b)`，其中 `a` 为 up 分支，`b` 为 gate 分支。
+    pub struct SwiGLU {
+        create: infiniopCreateSwiGLUDescriptor,
+        workspace: infiniopGetSwiGLUWorkspaceSize,
+        launch: infiniopSwiGLU,
+        destroy: infiniopDestroySwiGLUDescriptor,
+        check: check,
+        outputs: [c],
+        inputs: [a, b],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素 ReLU 激活 `y = max(x, 0)`。
+    pub struct Relu {
+        create: infiniopCreateReluDescriptor,
+        workspace: infiniopGetReluWorkspaceSize,
+        launch: infiniopRelu,
+        destroy: infiniopDestroyReluDescriptor,
+        check: check,
+        outputs: [y],
+        inputs: [x],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素 GELU 激活。
+    pub struct Gelu {
+        create: infiniopCreateGeluDescriptor,
+        workspace: infiniopGetGeluWorkspaceSize,
+        launch: infiniopGelu,
+        destroy: infiniopDestroyGeluDescriptor,
+        check: check,
+        outputs: [y],
+        inputs: [x],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素 SiLU 激活 `y = x * sigmoid(x)`。
+    pub struct Silu {
+        create: infiniopCreateSiluDescriptor,
+        workspace: infiniopGetSiluWorkspaceSize,
+        launch: infiniopSilu,
+        destroy: infiniopDestroySiluDescriptor,
+        check: check,
+        outputs: [y],
+        inputs: [x],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 逐元素截断 `y = min(max(x, min_val), max_val)`。
+    pub struct Clip {
+        create: infiniopCreateClipDescriptor,
+        workspace: infiniopGetClipWorkspaceSize,
+        launch: infiniopClip,
+        destroy: infiniopDestroyClipDescriptor,
+        check: check,
+        outputs: [y],
+        inputs: [x, min_val, max_val],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+/// 所有逐元素算子共用的校验：数据类型一致，输入形状与输出相同，输出不含广播维度。
+fn check(tensors: &[&Tensor]) -> Result<(), Error> {
+    let (&y, inputs) = tensors.split_first().unwrap();
+    for (i, t) in inputs.iter().enumerate() {
+        if t.dt() != y.dt() {
+            return Err(Error::BadDtype(format!(
+                "elementwise input {i} is {}, output is {}",
+                t.dt(),
+                y.dt(),
+            )));
+        }
+        if t.shape() != y.shape() {
+            return Err(Error::BadShape(format!(
+                "elementwise input {i} {:?} does not match output {:?}",
+                t.shape(),
+                y.shape(),
+            )));
+        }
+    }
+    if y.shape()
+        .iter()
+        .zip(y.strides())
+        .any(|(&d, &s)| d > 1 && s == 0)
+    {
+        return Err(Error::BadStrides(format!(
+            "elementwise output must not be broadcast, got strides {:?}",
+            y.strides(),
+        )));
+    }
+    Ok(())
+}
+
+/// 按广播规则将 `t` 扩展到 `shape`，扩展出的维度步长为 0。
+fn broadcast(t: &DevTensor, shape: &[usize]) -> Result<DevTensor, Error> {
+    if t.shape() == shape {
+        return Ok(t.clone());
+    }
+    let compatible = t.ndim() <= shape.len()
+        && t.shape()
+            .iter()
+            .rev()
+            .zip(shape.iter().rev())
+            .all(|(&d, &s)| d == s || d == 1);
+    if compatible {
+        Ok(t.broadcast_to(shape))
+    } else {
+        Err(Error::BadShape(format!(
+            "cannot broadcast {:?} to {shape:?}",
+            t.shape(),
+        )))
+    }
+}
+
+macro_rules! binary {
+    ($op:ident, $f:ident, $f_inplace:ident, $expr:literal) => {
+        impl Handle {
+            #[doc = concat!("在 `stream` 上计算 `", $expr, "`，`a`、`b` 按广播规则扩展到 `c` 的形状。")]
+            pub fn $f(
+                &self,
+                c: &mut DevTensor,
+                a: &DevTensor,
+                b: &DevTensor,
+                stream: &Stream,
+            ) -> Result<(), Error> {
+                let a = broadcast(a, c.shape())?;
+                let b = broadcast(b, c.shape())?;
+                let op = self.prepare(stream, &[c, &a, &b], &[], || $op::new(self, c, &a, &b))?;
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, c.as_mut_ptr(), a.as_ptr(), b.as_ptr(), stream)
+                })
+            }
+
+            #[doc = concat!("在 `stream` 上原地计算 `", $expr, "`，结果写回 `a`，`b` 按广播规则扩展到 `a` 的形状。")]
+            pub fn $f_inplace(
+                &self,
+                a: &mut DevTensor,
+                b: &DevTensor,
+                stream: &Stream,
+            ) -> Result<(), Error> {
+                let b = broadcast(b, a.shape())?;
+                let op = self.prepare(stream, &[a, a, &b], &[], || $op::new(self, a, a, &b))?;
+                let ptr = a.as_mut_ptr();
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, ptr, ptr, b.as_ptr(), stream)
+                })
+            }
+        }
+    };
+}
+
+macro_rules! unary {
+    ($op:ident, $f:ident, $f_inplace:ident, $expr:literal) => {
+        impl Handle {
+            #[doc = concat!("在 `stream` 上计算 `", $expr, "`。")]
+            pub fn $f(
+                &self,
+                y: &mut DevTensor,
+                x: &DevTensor,
+                stream: &Stream,
+            ) -> Result<(), Error> {
+                let op = self.prepare(stream, &[y, x], &[], || $op::new(self, y, x))?;
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, y.as_mut_ptr(), x.as_ptr(), stream)
+                })
+            }
+
+            #[doc = concat!("在 `stream` 上原地计算 `", $expr, "`，结果写回 `x`。")]
+            pub fn $f_inplace(&self, x: &mut DevTensor, stream: &Stream) -> Result<(), Error> {
+                let op = self.prepare(stream, &[x, x], &[], || $op::new(self, x, x))?;
+                let ptr = x.as_mut_ptr();
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, ptr, ptr, stream)
+                })
+            }
+        }
+    };
+}
+
+binary!(Add, add, add_inplace, "c = a + b");
+binary!(Sub, sub, sub_inplace, "c = a - b");
+binary!(Mul, mul, mul_inplace, "c = a * b");
+binary!(SwiGLU, swiglu, swiglu_inplace, "c = a * silu(b)");
+unary!(Relu, relu, relu_inplace, "y = relu(x)");
+unary!(Gelu, gelu, gelu_inplace, "y = gelu(x)");
+unary!(Silu, silu, silu_inplace, "y = silu(x)");
+
+impl Handle {
+    /// 在 `stream` 上计算 `y = min(max(x, min), max)`。
+    pub fn clip(
+        &self,
+        y: &mut DevTensor,
+        x: &DevTensor,
+        min: f32,
+        max: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let [min, max] = bounds(x, min, max, stream)?;
+        let ans = self
+            .prepare(stream, &[y, x, &min, &max], &[], || {
+                Clip::new(self, y, x, &min, &max)
+            })
+            .and_then(|op| {
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(
+                        workspace,
+                        y.as_mut_ptr(),
+                        x.as_ptr(),
+                        min.as_ptr(),
+                        max.as_ptr(),
+                        stream,
+                    )
+                })
+            });
+        free_bounds([min, max], stream);
+        ans
+    }
+
+    /// 在 `stream` 上原地计算 `x = min(max(x, min), max)`。
+    pub fn clip_inplace(
+        &self,
+        x: &mut DevTensor,
+        min: f32,
+        max: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let [min, max] = bounds(x, min, max, stream)?;
+        let ans = self
+            .prepare(stream, &[x, x, &min, &max], &[], || {
+                Clip::new(self, x, x, &min, &max)
+            })
+            .and_then(|op| {
+                let ptr = x.as_mut_ptr();
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, ptr, ptr, min.as_ptr(), max.as_ptr(), stream)
+                })
+            });
+        free_bounds([min, max], stream);
+        ans
+    }
+}
+
+/// 将截断的上下界上传为与 `x` 同类型的标量，并广播到 `x` 的形状。
+fn bounds(x: &Tensor, min: f32, max: f32, stream: &Stream) -> Result<[DevTensor; 2], Error> {
+    let dt = x.dt();
+    let blob = upload_floats(stream, dt, &[min, max])
+        .ok_or_else(|| Error::BadDtype(format!("clip requires a float tensor, got {dt}")))?;
+    let bounds = DevTensor::new(Tensor::contiguous(dt, [2]), blob);
+    Ok([0, 1].map(|i| bounds.narrow(0, i, 1).squeeze(0).broadcast_to(x.shape())))
+}
+
+/// 在 `stream` 上释放 [`bounds`] 上传的上下界，两者共享同一块内存，释放第二个时才真正释放。
+fn free_bounds(bounds: [DevTensor; 2], stream: &Stream) {
+    for t in bounds {
+        stream.free(t.into_blob())
+    }
+}
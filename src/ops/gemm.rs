@@ -0,0 +1,122 @@
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+
+operator! {
+    /// 通用矩阵乘 `c = alpha * a @ b + beta * c`。
+    ///
+    /// `a`、`b`、`c` 是二维矩阵或三维批量矩阵，转置通过步长表达。
+    pub struct Gemm {
+        create: infiniopCreateGemmDescriptor,
+        workspace: infiniopGetGemmWorkspaceSize,
+        launch: infiniopGemm,
+        destroy: infiniopDestroyGemmDescriptor,
+        check: check,
+        outputs: [c],
+        inputs: [a, b],
+        create_args: [],
+        launch_args: [alpha: f32, beta: f32],
+    }
+}
+
+fn check(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[c, a, b] = tensors else { unreachable!() };
+
+    if a.dt() != c.dt() || b.dt() != c.dt() {
+        return Err(Error::BadDtype(format!(
+            "gemm requires identical dtypes, got c: {}, a: {}, b: {}",
+            c.dt(),
+            a.dt(),
+            b.dt(),
+        )));
+    }
+
+    for (name, t) in [("c", c), ("a", a), ("b", b)] {
+        if !(2..=3).contains(&t.ndim()) {
+            return Err(Error::BadShape(format!(
+                "gemm {name} must be 2-D or 3-D, got {:?}",
+                t.shape(),
+            )));
+        }
+        let &[.., rs, cs] = t.strides() else {
+            unreachable!()
+        };
+        let ele = t.dt().nbytes() as isize;
+        if rs != ele && cs != ele {
+            return Err(Error::BadStrides(format!(
+                "gemm {name} needs one of its last two dimensions to be contiguous, got strides {:?}",
+                t.strides(),
+            )));
+        }
+    }
+
+    let (batch, m, n) = matrix(c);
+    let (batch_a, m_a, k) = matrix(a);
+    let (batch_b, k_b, n_b) = matrix(b);
+    if m_a != m || n_b != n || k_b != k {
+        return Err(Error::BadShape(format!(
+            "gemm shape mismatch: c {:?} = a {:?} @ b {:?}",
+            c.shape(),
+            a.shape(),
+            b.shape(),
+        )));
+    }
+    for (name, t, b) in [("a", a, batch_a), ("b", b, batch_b)] {
+        if b != batch && b != 1 {
+            return Err(Error::BadShape(format!(
+                "gemm {name} batch {:?} cannot broadcast to c batch {:?}",
+                t.shape(),
+                c.shape(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 将二维或三维张量拆分为 `(batch, rows, cols)`。
+fn matrix(t: &Tensor) -> (usize, usize, usize) {
+    match *t.shape() {
+        [r, c] => (1, r, c),
+        [b, r, c] => (b, r, c),
+        _ => unreachable!(),
+    }
+}
+
+/// 将 `t` 的批量维度广播到 `c` 的批量维度。
+fn broadcast_batch(t: &DevTensor, c: &DevTensor) -> DevTensor {
+    match (t.shape(), c.shape()) {
+        (&[r, k], &[batch, _, _]) => t.broadcast_to(&[batch, r, k]),
+        (&[1, r, k], &[batch, _, _]) if batch != 1 => t.broadcast_to(&[batch, r, k]),
+        _ => t.clone(),
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上计算 `c = alpha * a @ b + beta * c`。
+    ///
+    /// 二维或批量为 1 的 `a`、`b` 会被广播到 `c` 的批量维度；
+    /// 转置的输入直接以转置视图（参见 [`DevTensor::transpose`]）传入。
+    /// 工作空间取自 `stream` 的 [`Workspace`](crate::Workspace)。
+    pub fn gemm(
+        &self,
+        c: &mut DevTensor,
+        a: &DevTensor,
+        b: &DevTensor,
+        alpha: f32,
+        beta: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let a = broadcast_batch(a, c);
+        let b = broadcast_batch(b, c);
+        let op = self.prepare(stream, &[c, &a, &b], &[], || Gemm::new(self, c, &a, &b))?;
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(
+                workspace,
+                c.as_mut_ptr(),
+                a.as_ptr(),
+                b.as_ptr(),
+                alpha,
+                beta,
+                stream,
+            )
+        })
+    }
+}
@@ -0,0 +1,144 @@
+//! 基于 [`Operator`](crate::Operator) 的 infiniop 算子封装。
+//!
+//! 每个算子既提供由 [`operator!`](crate::operator) 生成的底层类型，
+//! 也在 [`Handle`](crate::Handle) 上提供接收 [`DevTensor`](crate::DevTensor) 的安全调用。
+
+mod attention;
+mod conv;
+mod elementwise;
+mod gemm;
+mod norm;
+mod random_sample;
+mod rearrange;
+mod reduce;
+mod rope;
+
+pub use attention::{Attention, CausalSoftmax};
+pub use conv::{Conv, ConvArgs, Pool, PoolArgs, PoolKind};
+pub use elementwise::{Add, Clip, Gelu, Mul, Relu, Silu, Sub, SwiGLU};
+pub use gemm::Gemm;
+pub use norm::{LayerNorm, RmsNorm};
+pub use random_sample::{RandomSample, SampleArgs, SampleRng};
+pub use rearrange::Rearrange;
+pub use reduce::ReduceMax;
+pub use rope::{Rope, rope_table};
+
+use crate::{DevBlob, DevTensor, Stream};
+use digit_layout::{DigitLayout, types};
+use half::{bf16, f16};
+
+/// 将 `data` 转换为浮点类型 `dt` 后在 `stream` 上传到设备，`dt` 不是浮点类型时返回 `None`。
+fn upload_floats(stream: &Stream, dt: DigitLayout, data: &[f32]) -> Option<DevBlob> {
+    Some(match dt {
+        types::F16 => stream.from_host(&data.iter().map(|&x| f16::from_f32(x)).collect::<Vec<_>>()),
+        types::BF16 => {
+            stream.from_host(&data.iter().map(|&x| bf16::from_f32(x)).collect::<Vec<_>>())
+        }
+        types::F32 => stream.from_host(data),
+        types::F64 => stream.from_host(&data.iter().map(|&x| x as f64).collect::<Vec<_>>()),
+        _ => return None,
+    })
+}
+
+/// 在 `stream` 上释放算子内部创建的临时张量。
+///
+/// 临时张量可能是输入的视图，此时只减少引用计数，不会释放调用者的内存。
+fn free_temps(stream: &Stream, temps: impl IntoIterator<Item = DevTensor>) {
+    for t in temps {
+        stream.free(t.into_blob())
+    }
+}
+
+/// 在 InfiniCore 的 CPU 后端上对照主机参考实现测试算子。
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod test_utils {
+    use super::upload_floats;
+    use crate::{DevByte, DevTensor, Device, Handle, Stream, Tensor};
+    use digit_layout::{DigitLayout, types};
+    use half::{bf16, f16};
+
+    /// CPU 设备上的句柄和流。
+    pub(super) fn cpu() -> (Handle, Stream) {
+        let device = Device::default();
+        (device.handle(), device.stream())
+    }
+
+    /// 将 `data` 转换为 `dt` 后上传为形状为 `shape` 的连续张量。
+    pub(super) fn upload(
+        stream: &Stream,
+        dt: DigitLayout,
+        shape: &[usize],
+        data: &[f32],
+    ) -> DevTensor {
+        assert_eq!(shape.iter().product::<usize>(), data.len());
+        let blob = upload_floats(stream, dt, data).unwrap();
+        DevTensor::new(Tensor::contiguous(dt, shape.iter().copied()), blob)
+    }
+
+    /// 等待 `stream` 完成后将浮点类型 `dt` 的连续数据 `blob` 下载到主机并转换为 `f32`，
+    /// `dt` 不是浮点类型时返回 `None`。
+    pub(super) fn download_floats(
+        stream: &Stream,
+        dt: DigitLayout,
+        blob: &[DevByte],
+    ) -> Option<Vec<f32>> {
+        fn download<T: Copy + Default>(stream: &Stream, blob: &[DevByte]) -> Vec<T> {
+            let mut host = vec![T::default(); blob.len() / size_of::<T>()];
+            stream.memcpy_d2h(&mut host, blob);
+            stream.synchronize();
+            host
+        }
+        Some(match dt {
+            types::F16 => download::<f16>(stream, blob)
+                .into_iter()
+                .map(f32::from)
+                .collect(),
+            types::BF16 => download::<bf16>(stream, blob)
+                .into_iter()
+                .map(f32::from)
+                .collect(),
+            types::F32 => download(stream, blob),
+            types::F64 => download::<f64>(stream, blob)
+                .into_iter()
+                .map(|x| x as f32)
+                .collect(),
+            _ => return None,
+        })
+    }
+
+    /// 同步下载连续的浮点张量，转换为 `f32`。
+    pub(super) fn download(stream: &Stream, t: &DevTensor) -> Vec<f32> {
+        assert!(t.is_contiguous());
+        let blob = &t.blob()[t.offset() as usize..][..t.numel() * t.dt().nbytes()];
+        download_floats(stream, t.dt(), blob).unwrap()
+    }
+
+    /// 把数据舍入到 `f16` 精度，使主机参考与设备看到相同的输入。
+    pub(super) fn round_f16(data: &[f32]) -> Vec<f32> {
+        data.iter().map(|&x| f16::from_f32(x).to_f32()).collect()
+    }
+
+    /// 逐元素比较，允许 `atol + rtol * |expected|` 的误差。
+    pub(super) fn assert_close(actual: &[f32], expected: &[f32], atol: f32, rtol: f32) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+            assert!(
+                (a - e).abs() <= atol + rtol * e.abs(),
+                "mismatch at {i}: actual {a}, expected {e}",
+            );
+        }
+    }
+
+    /// 确定性的测试数据，取值在 `[-1, 1)`。
+    pub(super) fn data(n: usize, seed: u32) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(2654435761) | 1;
+        (0..n)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 8) as f32 / (1 << 23) as f32 - 1.
+            })
+            .collect()
+    }
+}
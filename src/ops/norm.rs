@@ -0,0 +1,253 @@
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+use digit_layout::{DigitLayout, types};
+
+operator! {
+    /// RMS 归一化 `y = x / sqrt(mean(x^2) + epsilon) * w`，沿最后一维计算。
+    ///
+    /// `w` 的数据类型可以与 `x` 相同，也可以是 `F32`（例如 `F16` 激活配合 `F32` 权重）。
+    pub struct RmsNorm {
+        create: infiniopCreateRMSNormDescriptor,
+        workspace: infiniopGetRMSNormWorkspaceSize,
+        launch: infiniopRMSNorm,
+        destroy: infiniopDestroyRMSNormDescriptor,
+        check: check_rms_norm,
+        outputs: [y],
+        inputs: [x, w],
+        create_args: [epsilon: f32],
+        launch_args: [],
+    }
+}
+
+operator! {
+    /// 层归一化 `y = (x - mean(x)) / sqrt(var(x) + epsilon) * w + b`，沿最后一维计算。
+    ///
+    /// `w`、`b` 的数据类型可以与 `x` 相同，也可以是 `F32`。
+    pub struct LayerNorm {
+        create: infiniopCreateLayerNormDescriptor,
+        workspace: infiniopGetLayerNormWorkspaceSize,
+        launch: infiniopLayerNorm,
+        destroy: infiniopDestroyLayerNormDescriptor,
+        check: check_layer_norm,
+        outputs: [y],
+        inputs: [x, w, b],
+        create_args: [epsilon: f32],
+        launch_args: [],
+    }
+}
+
+fn check_rms_norm(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[y, x, w] = tensors else { unreachable!() };
+    check_norm(y, x, &[("w", w)])
+}
+
+fn check_layer_norm(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[y, x, w, b] = tensors else {
+        unreachable!()
+    };
+    check_norm(y, x, &[("w", w), ("b", b)])
+}
+
+fn check_norm(y: &Tensor, x: &Tensor, params: &[(&str, &Tensor)]) -> Result<(), Error> {
+    if y.dt() != x.dt() {
+        return Err(Error::BadDtype(format!(
+            "norm output {} differs from input {}",
+            y.dt(),
+            x.dt(),
+        )));
+    }
+    if y.shape() != x.shape() || !(2..=3).contains(&x.ndim()) {
+        return Err(Error::BadShape(format!(
+            "norm requires 2-D or 3-D y and x of the same shape, got y {:?}, x {:?}",
+            y.shape(),
+            x.shape(),
+        )));
+    }
+    let d = *x.shape().last().unwrap();
+    for &(name, t) in params {
+        if t.dt() != x.dt() && t.dt() != types::F32 {
+            return Err(Error::BadDtype(format!(
+                "norm {name} must be {} or {}, got {}",
+                x.dt(),
+                types::F32,
+                t.dt(),
+            )));
+        }
+        if t.shape() != [d] {
+            return Err(Error::BadShape(format!(
+                "norm {name} must be [{d}], got {:?}",
+                t.shape(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 在 `stream` 上创建一个长度为 `d`、所有元素为 `value` 的向量，用于补全缺省的权重或偏置。
+///
+/// `F64` 输入使用 `F64` 向量，其余使用 `F32` 向量。
+fn filled(x: DigitLayout, d: usize, value: f32, stream: &Stream) -> DevTensor {
+    if x == types::F64 {
+        let blob = stream.from_host(&vec![value as f64; d]);
+        DevTensor::new(Tensor::contiguous(types::F64, [d]), blob)
+    } else {
+        let blob = stream.from_host(&vec![value; d]);
+        DevTensor::new(Tensor::contiguous(types::F32, [d]), blob)
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上计算 RMS 归一化，参见 [`RmsNorm`]。
+    ///
+    /// `w` 为 `None` 时使用全 1 权重，临时权重在算子之后于 `stream` 上释放。
+    pub fn rms_norm(
+        &self,
+        y: &mut DevTensor,
+        x: &DevTensor,
+        w: Option<&DevTensor>,
+        epsilon: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let d = x.shape().last().copied().unwrap_or(0);
+        let ones = w.is_none().then(|| filled(x.dt(), d, 1., stream));
+        let w = w.or(ones.as_ref()).unwrap();
+        let ans = self
+            .prepare(stream, &[y, x, w], &[epsilon.to_bits() as _], || {
+                RmsNorm::new(self, y, x, w, epsilon)
+            })
+            .and_then(|op| {
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(workspace, y.as_mut_ptr(), x.as_ptr(), w.as_ptr(), stream)
+                })
+            });
+        if let Some(ones) = ones {
+            stream.free(ones.into_blob())
+        }
+        ans
+    }
+
+    /// 在 `stream` 上计算层归一化，参见 [`LayerNorm`]。
+    ///
+    /// `w` 为 `None` 时使用全 1 权重，`b` 为 `None` 时使用全 0 偏置，
+    /// 临时参数在算子之后于 `stream` 上释放。
+    pub fn layer_norm(
+        &self,
+        y: &mut DevTensor,
+        x: &DevTensor,
+        w: Option<&DevTensor>,
+        b: Option<&DevTensor>,
+        epsilon: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let d = x.shape().last().copied().unwrap_or(0);
+        let ones = w.is_none().then(|| filled(x.dt(), d, 1., stream));
+        let zeros = b.is_none().then(|| filled(x.dt(), d, 0., stream));
+        let w = w.or(ones.as_ref()).unwrap();
+        let b = b.or(zeros.as_ref()).unwrap();
+        let ans = self
+            .prepare(stream, &[y, x, w, b], &[epsilon.to_bits() as _], || {
+                LayerNorm::new(self, y, x, w, b, epsilon)
+            })
+            .and_then(|op| {
+                with_workspace(&*op, stream, |workspace| unsafe {
+                    op.launch(
+                        workspace,
+                        y.as_mut_ptr(),
+                        x.as_ptr(),
+                        w.as_ptr(),
+                        b.as_ptr(),
+                        stream,
+                    )
+                })
+            });
+        for t in ones.into_iter().chain(zeros) {
+            stream.free(t.into_blob())
+        }
+        ans
+    }
+}
+
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod tests {
+    use crate::ops::test_utils::{assert_close, cpu, data, download, round_f16, upload};
+    use digit_layout::types;
+
+    const N: usize = 3;
+    const D: usize = 16;
+    const EPS: f32 = 1e-5;
+
+    fn rms_norm_ref(x: &[f32], w: &[f32]) -> Vec<f32> {
+        x.chunks(D)
+            .flat_map(|row| {
+                let ms = row.iter().map(|x| x * x).sum::<f32>() / D as f32;
+                let k = 1. / (ms + EPS).sqrt();
+                row.iter().zip(w).map(move |(x, w)| x * k * w)
+            })
+            .collect()
+    }
+
+    fn layer_norm_ref(x: &[f32], w: &[f32], b: &[f32]) -> Vec<f32> {
+        x.chunks(D)
+            .flat_map(|row| {
+                let mean = row.iter().sum::<f32>() / D as f32;
+                let var = row.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / D as f32;
+                let k = 1. / (var + EPS).sqrt();
+                row.iter()
+                    .zip(w)
+                    .zip(b)
+                    .map(move |((x, w), b)| (x - mean) * k * w + b)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rms_norm_f16_f32_weight() {
+        let (handle, stream) = cpu();
+        let x_host = round_f16(&data(N * D, 1));
+        let w_host = data(D, 2);
+        let x = upload(&stream, types::F16, &[N, D], &x_host);
+        let w = upload(&stream, types::F32, &[D], &w_host);
+        let mut y = upload(&stream, types::F16, &[N, D], &[0.; N * D]);
+
+        handle.rms_norm(&mut y, &x, Some(&w), EPS, &stream).unwrap();
+        assert_close(
+            &download(&stream, &y),
+            &rms_norm_ref(&x_host, &w_host),
+            1e-2,
+            1e-2,
+        );
+
+        // 缺省权重为全 1
+        handle.rms_norm(&mut y, &x, None, EPS, &stream).unwrap();
+        assert_close(
+            &download(&stream, &y),
+            &rms_norm_ref(&x_host, &[1.; D]),
+            1e-2,
+            1e-2,
+        );
+    }
+
+    #[test]
+    fn layer_norm_f16_f32_weight() {
+        let (handle, stream) = cpu();
+        let x_host = round_f16(&data(N * D, 3));
+        let w_host = data(D, 4);
+        let b_host = data(D, 5);
+        let x = upload(&stream, types::F16, &[N, D], &x_host);
+        let w = upload(&stream, types::F32, &[D], &w_host);
+        let b = upload(&stream, types::F32, &[D], &b_host);
+        let mut y = upload(&stream, types::F16, &[N, D], &[0.; N * D]);
+
+        handle
+            .layer_norm(&mut y, &x, Some(&w), Some(&b), EPS, &stream)
+            .unwrap();
+        let expected = layer_norm_ref(&x_host, &w_host, &b_host);
+        assert_close(&download(&stream, &y), &expected, 1e-2, 1e-2);
+
+        // 缺省权重为全 1，缺省偏置为全 0
+        handle
+            .layer_norm(&mut y, &x, None, None, EPS, &stream)
+            .unwrap();
+        let expected = layer_norm_ref(&x_host, &[1.; D], &[0.; D]);
+        assert_close(&download(&stream, &y), &expected, 1e-2, 1e-2);
+    }
+}
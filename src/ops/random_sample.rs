@@ -0,0 +1,314 @@
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+use digit_layout::types;
+use std::ffi::c_int;
+
+operator! {
+    /// 从一维 `probs`（未归一化的 logits）中随机采样一个下标写入零维整数张量 `result`。
+    pub struct RandomSample {
+        create: infiniopCreateRandomSampleDescriptor,
+        workspace: infiniopGetRandomSampleWorkspaceSize,
+        launch: infiniopRandomSample,
+        destroy: infiniopDestroyRandomSampleDescriptor,
+        check: check,
+        outputs: [result],
+        inputs: [probs],
+        create_args: [],
+        launch_args: [random_val: f32, topp: f32, topk: c_int, temperature: f32],
+    }
+}
+
+fn check(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[result, probs] = tensors else {
+        unreachable!()
+    };
+    if ![types::I32, types::I64, types::U32, types::U64].contains(&result.dt()) {
+        return Err(Error::BadDtype(format!(
+            "random sample result must be an integer scalar, got {}",
+            result.dt(),
+        )));
+    }
+    if ![types::F16, types::BF16, types::F32, types::F64].contains(&probs.dt()) {
+        return Err(Error::BadDtype(format!(
+            "random sample logits must be float, got {}",
+            probs.dt(),
+        )));
+    }
+    if result.ndim() != 0 || probs.ndim() != 1 || probs.numel() == 0 {
+        return Err(Error::BadShape(format!(
+            "random sample requires a scalar result and non-empty 1-D logits, got result {:?}, logits {:?}",
+            result.shape(),
+            probs.shape(),
+        )));
+    }
+    Ok(())
+}
+
+/// 采样参数。
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SampleArgs {
+    /// 温度，logits 在 softmax 之前除以此值。
+    pub temperature: f32,
+    /// 核采样阈值，只在累积概率不超过此值的候选中采样。
+    pub top_p: f32,
+    /// 只在概率最大的 `top_k` 个候选中采样，0 表示不限制。
+    pub top_k: usize,
+}
+
+impl SampleArgs {
+    /// 总是选择概率最大的候选。
+    pub const ARG_MAX: Self = Self {
+        temperature: 0.,
+        top_p: 0.,
+        top_k: 1,
+    };
+
+    /// 判断参数是否退化为取最大值。
+    #[inline]
+    pub fn is_argmax(&self) -> bool {
+        self.temperature <= 0. || self.top_k == 1 || self.top_p <= 0.
+    }
+
+    /// 在主机上按与 infiniop 相同的算法从 `logits` 中采样，返回下标。
+    ///
+    /// 候选按 logits 降序排列，以 `exp((x - max) / temperature)` 的前缀和作为累积概率，
+    /// 取 `top_k` 和 `top_p` 两个截断中较小的累积概率乘以 `random` 作为阈值，
+    /// 返回第一个累积概率达到阈值的候选。取最大值时相同的值取下标最小的一个。
+    ///
+    /// # Panics
+    ///
+    /// 如果 `logits` 为空。
+    pub fn sample(&self, logits: &[f32], random: f32) -> usize {
+        assert!(!logits.is_empty(), "cannot sample from empty logits");
+        if self.is_argmax() {
+            return (0..logits.len())
+                .reduce(|best, i| if logits[i] > logits[best] { i } else { best })
+                .unwrap();
+        }
+
+        let mut order = (0..logits.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| logits[b].total_cmp(&logits[a]));
+        let max = logits[order[0]];
+        let cum = order
+            .iter()
+            .scan(0., |acc, &i| {
+                *acc += ((logits[i] - max) / self.temperature).exp();
+                Some(*acc)
+            })
+            .collect::<Vec<f32>>();
+        let k = self.top_k(logits.len());
+        let limit = random * cum[k - 1].min(cum[cum.len() - 1] * self.top_p);
+        let i = cum.iter().position(|&c| c >= limit).unwrap_or(k - 1);
+        order[i]
+    }
+
+    /// `n` 个候选时实际的 `top_k`。
+    #[inline]
+    fn top_k(&self, n: usize) -> usize {
+        match self.top_k {
+            0 => n,
+            k => k.min(n),
+        }
+    }
+}
+
+impl Default for SampleArgs {
+    #[inline]
+    fn default() -> Self {
+        Self::ARG_MAX
+    }
+}
+
+/// 为随机采样提供 `[0, 1)` 均匀分布随机数的可复现生成器（xorshift64*）。
+#[derive(Clone, Debug)]
+pub struct SampleRng(u64);
+
+impl SampleRng {
+    /// 以 `seed` 初始化生成器。
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 打散种子，避免全 0 状态
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)) | 1)
+    }
+
+    /// 生成下一个 `[0, 1)` 区间的随机数。
+    pub fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let x = self.0.wrapping_mul(0x2545_f491_4f6c_dd1d);
+        (x >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上按 `args` 从 `logits` 中采样，结果下标写入零维整数张量 `result`。
+    ///
+    /// `random` 是调用者提供的 `[0, 1)` 随机数，可以来自 [`SampleRng`]；
+    /// 当 [`SampleArgs::is_argmax`] 成立时忽略 `random`，直接取最大值。
+    pub fn random_sample(
+        &self,
+        result: &mut DevTensor,
+        logits: &DevTensor,
+        args: SampleArgs,
+        random: f32,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let (random, top_p, top_k, temperature) = if args.is_argmax() {
+            (0., 1., 1, 1.)
+        } else {
+            (
+                random,
+                args.top_p,
+                args.top_k(logits.numel()).min(c_int::MAX as usize) as c_int,
+                args.temperature,
+            )
+        };
+        let op = self.prepare(stream, &[result, logits], &[], || {
+            RandomSample::new(self, result, logits)
+        })?;
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(
+                workspace,
+                result.as_mut_ptr(),
+                logits.as_ptr(),
+                random,
+                top_p,
+                top_k,
+                temperature,
+                stream,
+            )
+        })
+    }
+
+    /// 在 `stream` 上取 `logits` 中最大值的下标写入 `result`，参见 [`Handle::random_sample`]。
+    #[inline]
+    pub fn argmax(
+        &self,
+        result: &mut DevTensor,
+        logits: &DevTensor,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        self.random_sample(result, logits, SampleArgs::ARG_MAX, 0., stream)
+    }
+
+    /// 在 `stream` 上按 `args` 从 `logits` 中采样，等待完成并将下标返回到主机。
+    pub fn sample(
+        &self,
+        logits: &DevTensor,
+        args: SampleArgs,
+        random: f32,
+        stream: &Stream,
+    ) -> Result<usize, Error> {
+        let mut result = DevTensor::new(Tensor::contiguous(types::U64, []), stream.malloc(8));
+        self.random_sample(&mut result, logits, args, random, stream)?;
+        let mut ans = [0u64];
+        stream.memcpy_d2h(&mut ans, result.blob());
+        stream.synchronize();
+        Ok(ans[0] as _)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SampleArgs, SampleRng};
+
+    const LOGITS: [f32; 5] = [0.5, 2., -1., 1.5, 0.];
+
+    #[test]
+    fn host_argmax() {
+        assert_eq!(SampleArgs::ARG_MAX.sample(&LOGITS, 0.9), 1);
+        let top1 = SampleArgs {
+            temperature: 1.,
+            top_p: 1.,
+            top_k: 1,
+        };
+        assert!(top1.is_argmax());
+        assert_eq!(top1.sample(&LOGITS, 0.9), 1);
+        // 相同的最大值取下标最小的一个
+        assert_eq!(SampleArgs::ARG_MAX.sample(&[1., 3., 3.], 0.), 1);
+    }
+
+    #[test]
+    fn host_top_k() {
+        let args = |top_k| SampleArgs {
+            temperature: 1.,
+            top_p: 1.,
+            top_k,
+        };
+        // 0 表示不限制，阈值接近 1 时取到概率最小的候选
+        assert!(!args(0).is_argmax());
+        assert_eq!(args(0).sample(&LOGITS, 0.9999), 2);
+        assert_eq!(args(2).sample(&LOGITS, 0.9999), 3);
+        assert_eq!(args(2).sample(&LOGITS, 0.), 1);
+        // 超过候选数的 top_k 等同于不限制
+        assert_eq!(args(100).sample(&LOGITS, 0.9999), 2);
+    }
+
+    #[test]
+    fn host_top_p() {
+        let args = SampleArgs {
+            temperature: 1.,
+            top_p: 0.5,
+            top_k: 0,
+        };
+        // exp(0) / sum 约为 0.47，前两个候选的累积概率覆盖 top_p
+        for i in 0..100 {
+            let random = i as f32 / 100.;
+            assert!([1, 3].contains(&args.sample(&LOGITS, random)));
+        }
+    }
+
+    #[test]
+    fn rng_range() {
+        let mut rng = SampleRng::new(42);
+        assert!(
+            (0..1000)
+                .map(|_| rng.next_f32())
+                .all(|x| (0. ..1.).contains(&x))
+        );
+        let mut a = SampleRng::new(7);
+        let mut b = SampleRng::new(7);
+        assert_eq!(a.next_f32(), b.next_f32());
+    }
+
+    #[cfg(all(infini_cpu, not(feature = "cpu")))]
+    #[test]
+    fn matches_host() {
+        use crate::ops::test_utils::{cpu, data, upload};
+        use digit_layout::types;
+
+        let (handle, stream) = cpu();
+        let logits_host = data(64, 11).iter().map(|x| x * 4.).collect::<Vec<_>>();
+        let logits = upload(&stream, types::F32, &[64], &logits_host);
+        let mut rng = SampleRng::new(0);
+        for args in [
+            SampleArgs::ARG_MAX,
+            SampleArgs {
+                temperature: 0.8,
+                top_p: 0.9,
+                top_k: 0,
+            },
+            SampleArgs {
+                temperature: 1.,
+                top_p: 1.,
+                top_k: 8,
+            },
+            SampleArgs {
+                temperature: 2.,
+                top_p: 0.3,
+                top_k: 50,
+            },
+        ] {
+            for _ in 0..16 {
+                let random = rng.next_f32();
+                assert_eq!(
+                    handle.sample(&logits, args, random, &stream).unwrap(),
+                    args.sample(&logits_host, random),
+                    "{args:?} with random {random}",
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    AsRaw, Descriptor, DevByte, DevTensor, Error, Handle, Operator, Stream, Tensor,
+    bindings::InfiniopDescriptor,
+};
+
+/// 在设备上按各自的步长将 `src` 复制到 `dst`，两者形状和数据类型必须相同。
+///
+/// 此算子不需要工作空间。
+pub struct Rearrange(Descriptor<InfiniopDescriptor>);
+
+impl Rearrange {
+    /// 创建算子描述符。
+    pub fn new(handle: &Handle, dst: &Tensor, src: &Tensor) -> Result<Self, Error> {
+        if dst.dt() != src.dt() {
+            return Err(Error::BadDtype(format!(
+                "rearrange dst {} differs from src {}",
+                dst.dt(),
+                src.dt(),
+            )));
+        }
+        if dst.shape() != src.shape() {
+            return Err(Error::BadShape(format!(
+                "rearrange dst {:?} differs from src {:?}",
+                dst.shape(),
+                src.shape(),
+            )));
+        }
+        Descriptor::try_new(
+            |ptr| unsafe {
+                crate::bindings::infiniopCreateRearrangeDescriptor(
+                    handle.as_raw(),
+                    ptr,
+                    dst.as_raw(),
+                    src.as_raw(),
+                )
+            },
+            crate::bindings::infiniopDestroyRearrangeDescriptor,
+        )
+        .map(Self)
+    }
+
+    /// 在 `stream` 上启动算子。
+    ///
+    /// # Safety
+    ///
+    /// 各指针必须指向与创建时描述符相符的设备内存，
+    /// 且在流上的计算完成之前保持有效。
+    pub unsafe fn launch(
+        &self,
+        dst: *mut DevByte,
+        src: *const DevByte,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        Error::check(unsafe {
+            crate::bindings::infiniopRearrange(
+                self.0.as_raw(),
+                dst.cast(),
+                src.cast(),
+                stream.as_raw(),
+            )
+        })
+    }
+}
+
+impl Operator for Rearrange {
+    const NAME: &'static str = "Rearrange";
+
+    #[inline]
+    fn descriptor(&self) -> &Descriptor<InfiniopDescriptor> {
+        &self.0
+    }
+
+    #[inline]
+    fn workspace_size(&self) -> usize {
+        0
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上将 `src` 复制到布局可能不同的 `dst`，参见 [`Rearrange`]。
+    pub fn rearrange(
+        &self,
+        dst: &mut DevTensor,
+        src: &DevTensor,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let op = self.prepare(stream, &[dst, src], &[], || Rearrange::new(self, dst, src))?;
+        unsafe { op.launch(dst.as_mut_ptr(), src.as_ptr(), stream) }
+    }
+}
+
+impl DevTensor {
+    /// 返回一个行优先连续存储的张量。
+    ///
+    /// 如果张量已经连续，直接返回共享存储的视图；
+    /// 否则在 `stream` 上分配新的存储并用 `handle` 在设备上完成重排，不经过主机。
+    /// 重排描述符缓存在 `handle` 中，反复调用时不会重新创建。
+    pub fn contiguous_on(&self, handle: &Handle, stream: &Stream) -> Result<DevTensor, Error> {
+        if self.is_contiguous() {
+            return Ok(self.clone());
+        }
+        let desc = Tensor::contiguous(self.dt(), self.shape().iter().copied());
+        let blob = stream.malloc(desc.numel() * self.dt().nbytes());
+        let mut dst = DevTensor::new(desc, blob);
+        match handle.rearrange(&mut dst, self, stream) {
+            Ok(()) => Ok(dst),
+            Err(e) => {
+                stream.free(dst.into_blob());
+                Err(e)
+            }
+        }
+    }
+}
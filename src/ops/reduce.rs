@@ -0,0 +1,386 @@
+use super::{free_temps, upload_floats};
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+use digit_layout::types;
+
+operator! {
+    /// 沿 `dim` 维取最大值，`y` 的形状与 `x` 相同但 `dim` 维长度为 1。
+    pub struct ReduceMax {
+        create: infiniopCreateReduceMaxDescriptor,
+        workspace: infiniopGetReduceMaxWorkspaceSize,
+        launch: infiniopReduceMax,
+        destroy: infiniopDestroyReduceMaxDescriptor,
+        check: check_reduce_max,
+        outputs: [y],
+        inputs: [x],
+        create_args: [dim: usize],
+        launch_args: [],
+    }
+}
+
+fn check_reduce_max(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[y, x] = tensors else { unreachable!() };
+    if y.dt() != x.dt() {
+        return Err(Error::BadDtype(format!(
+            "reduce max output {} differs from input {}",
+            y.dt(),
+            x.dt(),
+        )));
+    }
+    let reduced = y.ndim() == x.ndim()
+        && y.shape()
+            .iter()
+            .zip(x.shape())
+            .all(|(&dy, &dx)| dy == dx || dy == 1);
+    if !reduced {
+        return Err(Error::BadShape(format!(
+            "reduce max output {:?} is not a reduction of {:?}",
+            y.shape(),
+            x.shape(),
+        )));
+    }
+    Ok(())
+}
+
+/// 校验 `axes` 互不相同且在 `0..ndim` 范围内，返回升序排列的副本。
+fn sorted_axes(t: &Tensor, axes: &[usize]) -> Result<Vec<usize>, Error> {
+    let mut sorted = axes.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() != axes.len() || sorted.last().is_some_and(|&a| a >= t.ndim()) {
+        return Err(Error::BadShape(format!(
+            "invalid reduce axes {axes:?} for tensor of shape {:?}",
+            t.shape(),
+        )));
+    }
+    Ok(sorted)
+}
+
+/// 归约后的形状，`keepdim` 为真时被归约的维度保留为 1。
+fn reduced_shape(shape: &[usize], axes: &[usize], keepdim: bool) -> Vec<usize> {
+    shape
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &d)| match (axes.contains(&i), keepdim) {
+            (false, _) => Some(d),
+            (true, true) => Some(1),
+            (true, false) => None,
+        })
+        .collect()
+}
+
+/// 在 `stream` 上分配一个连续存储的张量。
+fn alloc(stream: &Stream, t: &Tensor, shape: &[usize]) -> DevTensor {
+    let desc = Tensor::contiguous(t.dt(), shape.iter().copied());
+    let blob = stream.malloc(desc.numel() * t.dt().nbytes());
+    DevTensor::new(desc, blob)
+}
+
+/// [`DevTensor::argmax`] 逐行启动算子的行数上限。
+const ARGMAX_LAUNCH_LIMIT: usize = 16;
+
+impl DevTensor {
+    /// 将 `axes` 移到最后并整理为 `[rows, k]` 的连续矩阵。
+    ///
+    /// 已经连续时返回共享存储的视图，否则返回新分配的张量，用完后应在 `stream` 上释放。
+    fn rows(&self, axes: &[usize], handle: &Handle, stream: &Stream) -> Result<DevTensor, Error> {
+        let order: Vec<_> = (0..self.ndim())
+            .filter(|i| !axes.contains(i))
+            .chain(axes.iter().copied())
+            .collect();
+        let [rows, k] = [&order[..self.ndim() - axes.len()], axes]
+            .map(|dims| dims.iter().map(|&i| self.shape()[i]).product::<usize>());
+        let x = self.permute(&order).contiguous_on(handle, stream)?;
+        Ok(x.reshape(&[rows, k]).unwrap())
+    }
+
+    /// 以矩阵乘 `[rows, k] @ ones[k, 1]` 实现的求和，结果乘以 `alpha`。
+    fn sum_scaled(
+        &self,
+        handle: &Handle,
+        axes: &[usize],
+        keepdim: bool,
+        alpha: impl FnOnce(usize) -> f32,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        let axes = sorted_axes(self, axes)?;
+        let shape = reduced_shape(self.shape(), &axes, keepdim);
+        let dt = self.dt();
+        let bad_dtype = || Error::BadDtype(format!("sum requires a float tensor, got {dt}"));
+        let k = axes.iter().map(|&i| self.shape()[i]).product::<usize>();
+        let rows = self
+            .numel()
+            .checked_div(k)
+            .unwrap_or_else(|| reduced_shape(self.shape(), &axes, false).iter().product());
+        if rows == 0 || k == 0 {
+            // 空的归约不启动算子：空集的和为 0，平均值为 0 / 0
+            let value = if k == 0 { 0. * alpha(0) } else { 0. };
+            let blob = upload_floats(stream, dt, &vec![value; rows]).ok_or_else(bad_dtype)?;
+            return Ok(DevTensor::new(Tensor::contiguous(dt, shape), blob));
+        }
+
+        let ones = upload_floats(stream, dt, &vec![1.; k]).ok_or_else(bad_dtype)?;
+        let ones = DevTensor::new(Tensor::contiguous(dt, [k, 1]), ones);
+        let x = match self.rows(&axes, handle, stream) {
+            Ok(x) => x,
+            Err(e) => {
+                free_temps(stream, [ones]);
+                return Err(e);
+            }
+        };
+        let mut y = alloc(stream, self, &[rows, 1]);
+        let ans = handle.gemm(&mut y, &x, &ones, alpha(k), 0., stream);
+        free_temps(stream, [x, ones]);
+        match ans {
+            Ok(()) => Ok(y.reshape(&shape).unwrap()),
+            Err(e) => {
+                free_temps(stream, [y]);
+                Err(e)
+            }
+        }
+    }
+
+    /// 在 `stream` 上沿 `axes` 求和。
+    ///
+    /// 以 [`Gemm`](super::Gemm) 与全 1 向量相乘实现，支持 `Gemm` 支持的浮点类型。
+    /// 沿长度为 0 的维度求和得到 0。
+    pub fn sum(
+        &self,
+        handle: &Handle,
+        axes: &[usize],
+        keepdim: bool,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        self.sum_scaled(handle, axes, keepdim, |_| 1., stream)
+    }
+
+    /// 在 `stream` 上沿 `axes` 求平均值，实现方式同 [`DevTensor::sum`]。
+    ///
+    /// 沿长度为 0 的维度求平均值得到 NaN。
+    pub fn mean(
+        &self,
+        handle: &Handle,
+        axes: &[usize],
+        keepdim: bool,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        self.sum_scaled(handle, axes, keepdim, |k| 1. / k as f32, stream)
+    }
+
+    /// 在 `stream` 上沿 `axes` 取最大值，参见 [`ReduceMax`]。
+    ///
+    /// 每个归约维度启动一次算子，中间结果在 `stream` 上释放。
+    /// 沿长度为 0 的维度取最大值没有定义，返回 [`Error::BadShape`]。
+    pub fn max(
+        &self,
+        handle: &Handle,
+        axes: &[usize],
+        keepdim: bool,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        let axes = sorted_axes(self, axes)?;
+        check_nonempty_axes(self, &axes, "max")?;
+        let mut x = self.clone();
+        for &axis in &axes {
+            let mut shape = x.shape().to_vec();
+            shape[axis] = 1;
+            let mut y = alloc(stream, self, &shape);
+            let ans = if y.numel() == 0 {
+                Ok(())
+            } else {
+                handle
+                    .prepare(stream, &[&y, &x], &[axis as _], || {
+                        ReduceMax::new(handle, &y, &x, axis)
+                    })
+                    .and_then(|op| {
+                        with_workspace(&*op, stream, |workspace| unsafe {
+                            op.launch(workspace, y.as_mut_ptr(), x.as_ptr(), stream)
+                        })
+                    })
+            };
+            // 第一轮的 x 是 self 的视图，释放只减少引用计数
+            free_temps(stream, [std::mem::replace(&mut x, y)]);
+            if let Err(e) = ans {
+                free_temps(stream, [x]);
+                return Err(e);
+            }
+        }
+        if !keepdim {
+            for &axis in axes.iter().rev() {
+                x = x.squeeze(axis);
+            }
+        }
+        Ok(x)
+    }
+
+    /// 在 `stream` 上沿 `axes` 取最小值，以 `-max(-x)` 实现。
+    pub fn min(
+        &self,
+        handle: &Handle,
+        axes: &[usize],
+        keepdim: bool,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        let zero = upload_floats(stream, self.dt(), &[0.]).ok_or_else(|| {
+            Error::BadDtype(format!("min requires a float tensor, got {}", self.dt()))
+        })?;
+        let zero = DevTensor::new(Tensor::contiguous(self.dt(), []), zero);
+
+        let mut neg = alloc(stream, self, self.shape());
+        let ans = handle
+            .sub(&mut neg, &zero, self, stream)
+            .and_then(|()| neg.max(handle, axes, keepdim, stream))
+            .and_then(|mut y| {
+                let x = y.clone();
+                match handle.sub(&mut y, &zero, &x, stream) {
+                    Ok(()) => Ok(y),
+                    Err(e) => {
+                        free_temps(stream, [x, y]);
+                        Err(e)
+                    }
+                }
+            });
+        free_temps(stream, [zero, neg]);
+        ans
+    }
+
+    /// 在 `stream` 上沿 `axis` 取最大值的下标，结果为 `I64` 张量。
+    ///
+    /// infiniop 的 [`RandomSample`](super::RandomSample) 只接受一行输入，这里逐行调用
+    /// [`Handle::argmax`]，适合行数较少的场景（例如贪心解码）。
+    /// 行数超过 16 时逐行启动的开销过大，返回 [`Error::NotImplemented`]。
+    /// 沿长度为 0 的维度取下标没有定义，返回 [`Error::BadShape`]。
+    pub fn argmax(
+        &self,
+        handle: &Handle,
+        axis: usize,
+        keepdim: bool,
+        stream: &Stream,
+    ) -> Result<DevTensor, Error> {
+        let axes = sorted_axes(self, &[axis])?;
+        check_nonempty_axes(self, &axes, "argmax")?;
+        let rows = self.numel() / self.shape()[axis];
+        if rows > ARGMAX_LAUNCH_LIMIT {
+            return Err(Error::NotImplemented(format!(
+                "argmax over {rows} rows of tensor {:?}, at most {ARGMAX_LAUNCH_LIMIT} rows are supported",
+                self.shape(),
+            )));
+        }
+        let shape = reduced_shape(self.shape(), &axes, keepdim);
+        let desc = Tensor::contiguous(types::I64, shape);
+        let x = self.rows(&axes, handle, stream)?;
+
+        let y = DevTensor::new(desc, stream.malloc(rows * size_of::<i64>()));
+        let flat = y.reshape(&[rows]).unwrap();
+        let ans = (0..rows).try_for_each(|r| {
+            let mut index = flat.narrow(0, r, 1).squeeze(0);
+            handle.argmax(&mut index, &x.narrow(0, r, 1).squeeze(0), stream)
+        });
+        free_temps(stream, [x, flat]);
+        match ans {
+            Ok(()) => Ok(y),
+            Err(e) => {
+                free_temps(stream, [y]);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 校验被归约的维度都不为空，没有单位元的归约（最大值、下标）在空集上没有定义。
+fn check_nonempty_axes(t: &Tensor, axes: &[usize], name: &str) -> Result<(), Error> {
+    if axes.iter().any(|&i| t.shape()[i] == 0) {
+        return Err(Error::BadShape(format!(
+            "{name} over empty axes {axes:?} of tensor {:?}",
+            t.shape(),
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod tests {
+    use crate::{
+        DevTensor, Error,
+        ops::test_utils::{assert_close, cpu, data, download, upload},
+    };
+    use digit_layout::types;
+
+    fn download_i64(stream: &crate::Stream, t: &DevTensor) -> Vec<i64> {
+        let mut host = vec![0i64; t.numel()];
+        stream.memcpy_d2h(&mut host, t.blob());
+        stream.synchronize();
+        host
+    }
+
+    #[test]
+    fn reduce_matches_host() {
+        let (handle, stream) = cpu();
+        let (m, n) = (20, 6);
+        let x_host = data(m * n, 31);
+        let x = upload(&stream, types::F32, &[m, n], &x_host);
+        let rows = x_host.chunks(n).collect::<Vec<_>>();
+        let cols = (0..n)
+            .map(|j| rows.iter().map(|r| r[j]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let fold = |v: &[f32], f: fn(f32, f32) -> f32| v.iter().copied().reduce(f).unwrap();
+
+        let sum = x.sum(&handle, &[1], false, &stream).unwrap();
+        let expected = rows.iter().map(|r| r.iter().sum()).collect::<Vec<f32>>();
+        assert_close(&download(&stream, &sum), &expected, 1e-5, 1e-5);
+
+        let mean = x.mean(&handle, &[0], true, &stream).unwrap();
+        assert_eq!(mean.shape(), [1, n]);
+        let expected = cols
+            .iter()
+            .map(|c| c.iter().sum::<f32>() / m as f32)
+            .collect::<Vec<_>>();
+        assert_close(&download(&stream, &mean), &expected, 1e-5, 1e-5);
+
+        let max = x.max(&handle, &[0, 1], false, &stream).unwrap();
+        assert_eq!(max.shape(), [0usize; 0]);
+        assert_close(&download(&stream, &max), &[fold(&x_host, f32::max)], 0., 0.);
+
+        let min = x.min(&handle, &[0], false, &stream).unwrap();
+        let expected = cols.iter().map(|c| fold(c, f32::min)).collect::<Vec<_>>();
+        assert_close(&download(&stream, &min), &expected, 0., 0.);
+
+        let argmax = |v: &[f32]| (0..v.len()).reduce(|b, i| if v[i] > v[b] { i } else { b });
+        let by_row = x.argmax(&handle, 0, false, &stream).unwrap();
+        let expected = cols
+            .iter()
+            .map(|c| argmax(c).unwrap() as i64)
+            .collect::<Vec<_>>();
+        assert_eq!(download_i64(&stream, &by_row), expected);
+        let keepdim = x.narrow(0, 0, 4).argmax(&handle, 1, true, &stream).unwrap();
+        assert_eq!(keepdim.shape(), [4, 1]);
+        let expected = rows[..4]
+            .iter()
+            .map(|r| argmax(r).unwrap() as i64)
+            .collect::<Vec<_>>();
+        assert_eq!(download_i64(&stream, &keepdim), expected);
+        // 行数过多时不回退到主机
+        assert!(matches!(
+            x.argmax(&handle, 1, false, &stream),
+            Err(Error::NotImplemented(_))
+        ));
+    }
+
+    #[test]
+    fn reduce_empty_axes() {
+        let (handle, stream) = cpu();
+        let x = upload(&stream, types::F32, &[3, 0], &[]);
+
+        let sum = x.sum(&handle, &[1], false, &stream).unwrap();
+        assert_eq!(download(&stream, &sum), [0.; 3]);
+        let mean = x.mean(&handle, &[1], false, &stream).unwrap();
+        assert!(download(&stream, &mean).iter().all(|x| x.is_nan()));
+        assert!(x.max(&handle, &[1], false, &stream).is_err());
+        assert!(x.argmax(&handle, 1, false, &stream).is_err());
+
+        // 保留的维度为空时结果也为空
+        let sum = x.sum(&handle, &[0], false, &stream).unwrap();
+        assert_eq!(sum.shape(), [0]);
+        let max = x.max(&handle, &[0], true, &stream).unwrap();
+        assert_eq!(max.shape(), [1, 0]);
+    }
+}
@@ -0,0 +1,198 @@
+use super::upload_floats;
+use crate::{DevTensor, Error, Handle, Stream, Tensor, operator, operator::with_workspace};
+use digit_layout::{DigitLayout, types};
+
+operator! {
+    /// 旋转位置编码，对 `x` 的每个注意力头按 `pos_ids` 查表旋转后写入 `y`。
+    ///
+    /// `x`、`y` 的形状为 `[seq_len, n_head, head_dim]`，`pos_ids` 为长度 `seq_len` 的整数张量，
+    /// `sin_table`、`cos_table` 的形状为 `[max_len, head_dim / 2]`。`y` 可以与 `x` 是同一块内存。
+    pub struct Rope {
+        create: infiniopCreateRoPEDescriptor,
+        workspace: infiniopGetRoPEWorkspaceSize,
+        launch: infiniopRoPE,
+        destroy: infiniopDestroyRoPEDescriptor,
+        check: check,
+        outputs: [y],
+        inputs: [x, pos_ids, sin_table, cos_table],
+        create_args: [],
+        launch_args: [],
+    }
+}
+
+fn check(tensors: &[&Tensor]) -> Result<(), Error> {
+    let &[y, x, pos, sin, cos] = tensors else {
+        unreachable!()
+    };
+
+    if y.dt() != x.dt() || sin.dt() != x.dt() || cos.dt() != x.dt() {
+        return Err(Error::BadDtype(format!(
+            "rope requires identical dtypes for y, x and tables, got y: {}, x: {}, sin: {}, cos: {}",
+            y.dt(),
+            x.dt(),
+            sin.dt(),
+            cos.dt(),
+        )));
+    }
+    if ![types::I32, types::I64, types::U32, types::U64].contains(&pos.dt()) {
+        return Err(Error::BadDtype(format!(
+            "rope pos_ids must be an integer tensor, got {}",
+            pos.dt(),
+        )));
+    }
+
+    let &[seq_len, _, dh] = x.shape() else {
+        return Err(Error::BadShape(format!(
+            "rope x must be [seq_len, n_head, head_dim], got {:?}",
+            x.shape(),
+        )));
+    };
+    if y.shape() != x.shape() || dh % 2 != 0 {
+        return Err(Error::BadShape(format!(
+            "rope requires y and x of the same shape with even head_dim, got y {:?}, x {:?}",
+            y.shape(),
+            x.shape(),
+        )));
+    }
+    if pos.shape() != [seq_len] {
+        return Err(Error::BadShape(format!(
+            "rope pos_ids must be [{seq_len}], got {:?}",
+            pos.shape(),
+        )));
+    }
+    for (name, t) in [("sin", sin), ("cos", cos)] {
+        if !matches!(*t.shape(), [_, half] if half == dh / 2) {
+            return Err(Error::BadShape(format!(
+                "rope {name} table must be [max_len, {}], got {:?}",
+                dh / 2,
+                t.shape(),
+            )));
+        }
+    }
+    for (name, t) in [("y", y), ("x", x)] {
+        if t.strides()[2] != t.dt().nbytes() as isize {
+            return Err(Error::BadStrides(format!(
+                "rope {name} must be contiguous in head_dim, got strides {:?}",
+                t.strides(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 在主机上计算 RoPE 的 `[sin, cos]` 表。
+///
+/// 两张表的形状均为 `[max_len, head_dim / 2]`，按行优先排列，
+/// 第 `p` 行第 `i` 列对应角度 `p * theta^(-2i / head_dim)`。
+pub fn rope_table(theta: f32, max_len: usize, head_dim: usize) -> [Vec<f32>; 2] {
+    let half = head_dim / 2;
+    let mut sin = Vec::with_capacity(max_len * half);
+    let mut cos = Vec::with_capacity(max_len * half);
+    for p in 0..max_len {
+        for i in 0..half {
+            let freq = (theta as f64).powf(-2. * i as f64 / head_dim as f64);
+            let (s, c) = (p as f64 * freq).sin_cos();
+            sin.push(s as f32);
+            cos.push(c as f32);
+        }
+    }
+    [sin, cos]
+}
+
+impl Stream {
+    /// 计算 RoPE 的 `[sin, cos]` 表（参见 [`rope_table`]）并以 `dt` 类型上传到设备。
+    ///
+    /// # Panics
+    ///
+    /// 如果 `dt` 不是浮点类型。
+    pub fn rope_table(
+        &self,
+        dt: DigitLayout,
+        theta: f32,
+        max_len: usize,
+        head_dim: usize,
+    ) -> [DevTensor; 2] {
+        rope_table(theta, max_len, head_dim).map(|table| {
+            let blob = upload_floats(self, dt, &table)
+                .unwrap_or_else(|| panic!("rope table must be a float type, got {dt}"));
+            DevTensor::new(Tensor::contiguous(dt, [max_len, head_dim / 2]), blob)
+        })
+    }
+}
+
+impl Handle {
+    /// 在 `stream` 上对 `t` 原地施加旋转位置编码，参见 [`Rope`]。
+    ///
+    /// `t` 可以是从融合的 QKV 张量中切出的带步长视图，只要求 `head_dim` 维连续。
+    pub fn rope(
+        &self,
+        t: &mut DevTensor,
+        pos_ids: &DevTensor,
+        sin: &DevTensor,
+        cos: &DevTensor,
+        stream: &Stream,
+    ) -> Result<(), Error> {
+        let op = self.prepare(stream, &[t, t, pos_ids, sin, cos], &[], || {
+            Rope::new(self, t, t, pos_ids, sin, cos)
+        })?;
+        let ptr = t.as_mut_ptr();
+        with_workspace(&*op, stream, |workspace| unsafe {
+            op.launch(
+                workspace,
+                ptr,
+                ptr,
+                pos_ids.as_ptr(),
+                sin.as_ptr(),
+                cos.as_ptr(),
+                stream,
+            )
+        })
+    }
+}
+
+#[cfg(all(test, infini_cpu, not(feature = "cpu")))]
+mod tests {
+    use super::rope_table;
+    use crate::{
+        DevTensor, Tensor,
+        ops::test_utils::{assert_close, cpu, data, download, upload},
+    };
+    use digit_layout::types;
+
+    #[test]
+    fn rope_matches_table() {
+        const THETA: f32 = 1e4;
+        const MAX_LEN: usize = 16;
+        const NH: usize = 2;
+        const DH: usize = 8;
+        let pos = [3u32, 0, 15, 1];
+        let seq = pos.len();
+
+        let (handle, stream) = cpu();
+        let x_host = data(seq * NH * DH, 7);
+        let mut x = upload(&stream, types::F32, &[seq, NH, DH], &x_host);
+        let pos_ids = DevTensor::new(
+            Tensor::contiguous(types::U32, [seq]),
+            stream.from_host(&pos),
+        );
+        let [sin, cos] = stream.rope_table(types::F32, THETA, MAX_LEN, DH);
+        handle.rope(&mut x, &pos_ids, &sin, &cos, &stream).unwrap();
+
+        // 相邻的两个元素组成一对旋转
+        let [sin_host, cos_host] = rope_table(THETA, MAX_LEN, DH);
+        let mut expected = x_host.clone();
+        for (s, &p) in pos.iter().enumerate() {
+            for h in 0..NH {
+                let row = &mut expected[(s * NH + h) * DH..][..DH];
+                for i in 0..DH / 2 {
+                    let sin = sin_host[p as usize * DH / 2 + i];
+                    let cos = cos_host[p as usize * DH / 2 + i];
+                    let [a, b] = [row[2 * i], row[2 * i + 1]];
+                    row[2 * i] = a * cos - b * sin;
+                    row[2 * i + 1] = a * sin + b * cos;
+                }
+            }
+        }
+        assert_close(&download(&stream, &x), &expected, 1e-5, 1e-4);
+    }
+}
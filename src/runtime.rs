@@ -0,0 +1,155 @@
+use crate::{Device, Error};
+use std::{fmt, sync::OnceLock};
+
+/// 设备内存的分配方式。
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Allocator {
+    /// [`Stream::malloc`](crate::Stream::malloc) 和 [`Stream::free`](crate::Stream::free)
+    /// 使用流序分配 `infinirtMallocAsync`/`infinirtFreeAsync`。
+    #[default]
+    Stream,
+    /// 流上的分配也使用同步的 `infinirtMalloc`/`infinirtFree`，释放前先同步流。
+    /// 用于不支持流序分配的后端。
+    Sync,
+}
+
+/// 运行时配置。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Config {
+    /// 初始化时在调用线程上激活的默认设备。
+    pub device: Device,
+    /// 设备内存的分配方式。
+    pub allocator: Allocator,
+    /// 加载 InfiniCore 库的选项。
+    #[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+    pub load: crate::LoadOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            device: Device::default(),
+            allocator: Allocator::default(),
+            #[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+            load: Default::default(),
+        }
+    }
+}
+
+/// 初始化运行时失败。
+#[derive(Debug)]
+pub enum InitError {
+    /// 运行时已经用另一份配置初始化过。
+    AlreadyInitialized(Config),
+    /// 之前的初始化已经失败，运行时不会重新初始化。
+    Failed(String),
+    /// `infinirtInit` 或激活默认设备失败。
+    Runtime(Error),
+    /// 加载 InfiniCore 库失败。
+    #[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+    Load(crate::LoadError),
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AlreadyInitialized(config) => {
+                write!(f, "runtime already initialized with {config:?}")
+            }
+            Self::Failed(msg) => write!(f, "runtime initialization failed earlier: {msg}"),
+            Self::Runtime(e) => write!(f, "failed to initialize runtime: {e}"),
+            #[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+            Self::Load(e) => write!(f, "failed to initialize runtime: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InitError {}
+
+/// 全进程唯一的 InfiniCore 运行时。
+///
+/// 运行时在 [`init_with`] 或第一次调用 InfiniCore 时（按默认配置）初始化，之后不再改变。
+/// 初始化只会执行一次，多个线程同时初始化时只有一个线程调用 `infinirtInit`。
+#[derive(Debug)]
+pub struct Runtime {
+    config: Config,
+}
+
+static RUNTIME: OnceLock<Result<Runtime, String>> = OnceLock::new();
+
+/// 按 `config` 初始化运行时。
+///
+/// 运行时已经初始化时，配置相同则直接返回，否则返回 [`InitError::AlreadyInitialized`]。
+pub fn init_with(config: Config) -> Result<&'static Runtime, InitError> {
+    let mut error = None;
+    let runtime = RUNTIME.get_or_init(|| {
+        Runtime::init(config.clone()).map_err(|e| {
+            let msg = e.to_string();
+            error = Some(e);
+            msg
+        })
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+    match runtime {
+        Ok(runtime) if runtime.config == config => Ok(runtime),
+        Ok(runtime) => Err(InitError::AlreadyInitialized(runtime.config.clone())),
+        Err(msg) => Err(InitError::Failed(msg.clone())),
+    }
+}
+
+impl Runtime {
+    fn init(config: Config) -> Result<Self, InitError> {
+        #[cfg(all(feature = "dlopen", not(feature = "cpu")))]
+        crate::load(&config.load).map_err(InitError::Load)?;
+        // 这里不能用 `infini!`，它会再次进入初始化
+        let Device { ty, id } = config.device;
+        unsafe {
+            Error::check(crate::bindings::infinirtInit()).map_err(InitError::Runtime)?;
+            Error::check(crate::bindings::infinirtSetDevice(ty, id)).map_err(InitError::Runtime)?;
+        }
+        Ok(Self { config })
+    }
+
+    /// 取得运行时，尚未初始化时按默认配置初始化。
+    ///
+    /// # Panics
+    ///
+    /// 如果初始化失败。
+    pub fn get() -> &'static Self {
+        match RUNTIME.get() {
+            Some(Ok(runtime)) => runtime,
+            Some(Err(msg)) => panic!("{msg}"),
+            None => match init_with(Config::default()) {
+                Ok(runtime) => runtime,
+                // 另一个线程抢先用其他配置完成了初始化
+                Err(InitError::AlreadyInitialized(_)) => Self::try_get().unwrap(),
+                Err(e) => panic!("{e}"),
+            },
+        }
+    }
+
+    /// 取得已经初始化的运行时，不触发初始化。
+    pub fn try_get() -> Option<&'static Self> {
+        RUNTIME.get()?.as_ref().ok()
+    }
+
+    /// 初始化时使用的配置。
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 默认设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.config.device
+    }
+
+    /// 设备内存的分配方式。
+    #[inline]
+    pub fn allocator(&self) -> Allocator {
+        self.config.allocator
+    }
+}
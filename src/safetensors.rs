@@ -0,0 +1,483 @@
+use crate::{DevTensor, Device, Stream, Tensor, layouts};
+use digit_layout::{DigitLayout, types};
+use memmap2::Mmap;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::File,
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// 一个或一组分片的 `.safetensors` 文件，文件内容以内存映射的方式访问。
+///
+/// 张量数据只有在 [`Safetensors::load`] 或 [`Safetensors::load_all`] 时才上传到设备，
+/// 上传的每个张量独占一块 [`DevBlob`](crate::DevBlob)，行优先连续存储。
+pub struct Safetensors {
+    files: Vec<Mmap>,
+    tensors: BTreeMap<String, TensorInfo>,
+    metadata: HashMap<String, String>,
+}
+
+/// `.safetensors` 文件中一个张量的信息。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TensorInfo {
+    /// 数据类型。
+    pub dt: DigitLayout,
+    /// 形状。
+    pub shape: Vec<usize>,
+    file: usize,
+    range: Range<usize>,
+}
+
+impl TensorInfo {
+    /// 张量数据的字节数。
+    #[inline]
+    pub fn nbytes(&self) -> usize {
+        self.range.len()
+    }
+}
+
+/// 读取 `.safetensors` 文件失败。
+#[derive(Debug)]
+pub enum SafetensorsError {
+    /// 无法打开或映射文件。
+    Io {
+        /// 出错的文件。
+        path: PathBuf,
+        /// 底层的 IO 错误。
+        source: io::Error,
+    },
+    /// 文件头或分片索引不符合格式。
+    Format {
+        /// 出错的文件。
+        path: PathBuf,
+        /// 具体原因。
+        msg: String,
+    },
+    /// 张量的数据类型无法映射到 `DigitLayout`。
+    Dtype {
+        /// 张量名。
+        name: String,
+        /// 文件中的数据类型。
+        dtype: String,
+    },
+    /// 没有这个名字的张量。
+    NotFound(String),
+}
+
+impl fmt::Display for SafetensorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::Format { path, msg } => {
+                write!(f, "invalid safetensors {}: {msg}", path.display())
+            }
+            Self::Dtype { name, dtype } => {
+                write!(f, "tensor {name} has unsupported dtype {dtype}")
+            }
+            Self::NotFound(name) => write!(f, "tensor {name} not found"),
+        }
+    }
+}
+
+impl std::error::Error for SafetensorsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// 将 safetensors 的数据类型名映射到 `DigitLayout`。
+pub fn dtype(name: &str) -> Option<DigitLayout> {
+    Some(match name {
+        "BOOL" => types::Bool,
+        "U8" => types::U8,
+        "I8" => types::I8,
+        "U16" => types::U16,
+        "I16" => types::I16,
+        "U32" => types::U32,
+        "I32" => types::I32,
+        "U64" => types::U64,
+        "I64" => types::I64,
+        "F8_E4M3" => layouts::F8,
+        "F16" => types::F16,
+        "BF16" => types::BF16,
+        "F32" => types::F32,
+        "F64" => types::F64,
+        "C64" => layouts::C64,
+        _ => return None,
+    })
+}
+
+impl Safetensors {
+    /// 打开一个 `.safetensors` 文件，或者分片模型的索引文件（如 `model.safetensors.index.json`）。
+    ///
+    /// 索引文件的 `weight_map` 中列出的分片按相对于索引文件的路径打开。
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SafetensorsError> {
+        let path = path.as_ref();
+        let mut ans = Self {
+            files: Vec::new(),
+            tensors: BTreeMap::new(),
+            metadata: HashMap::new(),
+        };
+        if path.extension().is_some_and(|ext| ext == "json") {
+            ans.open_index(path)?
+        } else {
+            ans.open_file(path)?
+        }
+        Ok(ans)
+    }
+
+    fn open_index(&mut self, path: &Path) -> Result<(), SafetensorsError> {
+        let format = |msg: String| SafetensorsError::Format {
+            path: path.into(),
+            msg,
+        };
+        let text = std::fs::read(path).map_err(|source| SafetensorsError::Io {
+            path: path.into(),
+            source,
+        })?;
+        let index: Value =
+            serde_json::from_slice(&text).map_err(|e| format(format!("bad index json: {e}")))?;
+        if let Some(metadata) = index.get("metadata").and_then(Value::as_object) {
+            for (k, v) in metadata {
+                let v = v.as_str().map_or_else(|| v.to_string(), str::to_string);
+                self.metadata.insert(k.clone(), v);
+            }
+        }
+        let Some(weight_map) = index.get("weight_map").and_then(Value::as_object) else {
+            return Err(format("missing weight_map".into()));
+        };
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut shards = weight_map
+            .values()
+            .map(|v| v.as_str().ok_or_else(|| format(format!("bad shard {v}"))))
+            .collect::<Result<Vec<_>, _>>()?;
+        shards.sort_unstable();
+        shards.dedup();
+        for shard in shards {
+            self.open_file(&dir.join(shard))?
+        }
+
+        match weight_map
+            .keys()
+            .find(|name| !self.tensors.contains_key(*name))
+        {
+            Some(name) => Err(format(format!("tensor {name} missing from its shard"))),
+            None => Ok(()),
+        }
+    }
+
+    fn open_file(&mut self, path: &Path) -> Result<(), SafetensorsError> {
+        let io = |source| SafetensorsError::Io {
+            path: path.into(),
+            source,
+        };
+        let format = |msg: String| SafetensorsError::Format {
+            path: path.into(),
+            msg,
+        };
+
+        let file = File::open(path).map_err(io)?;
+        // SAFETY: 映射期间文件不应被修改，这与其他 safetensors 实现的约定相同
+        let mmap = unsafe { Mmap::map(&file) }.map_err(io)?;
+
+        // 8 字节小端的头长度，然后是 JSON 头，之后是数据区
+        let Some((len, rest)) = mmap.split_first_chunk::<8>() else {
+            return Err(format("file too short".into()));
+        };
+        let len = u64::from_le_bytes(*len) as usize;
+        let Some(header) = rest.get(..len) else {
+            return Err(format(format!("header of {len} bytes exceeds file")));
+        };
+        let base = 8 + len;
+        let data = mmap.len() - base;
+
+        let header: serde_json::Map<String, Value> =
+            serde_json::from_slice(header).map_err(|e| format(format!("bad header json: {e}")))?;
+        let id = self.files.len();
+        for (name, info) in header {
+            if name == "__metadata__" {
+                for (k, v) in info.as_object().into_iter().flatten() {
+                    if let Some(v) = v.as_str() {
+                        self.metadata.insert(k.clone(), v.into());
+                    }
+                }
+                continue;
+            }
+
+            let bad = || format(format!("bad entry for tensor {name}"));
+            let dtype_name = info["dtype"].as_str().ok_or_else(bad)?;
+            let shape = info["shape"]
+                .as_array()
+                .ok_or_else(bad)?
+                .iter()
+                .map(|d| d.as_u64().map(|d| d as usize))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(bad)?;
+            let [start, end] = info["data_offsets"]
+                .as_array()
+                .and_then(|offsets| match &offsets[..] {
+                    [start, end] => Some([start.as_u64()? as usize, end.as_u64()? as usize]),
+                    _ => None,
+                })
+                .ok_or_else(bad)?;
+
+            let Some(dt) = dtype(dtype_name) else {
+                return Err(SafetensorsError::Dtype {
+                    name,
+                    dtype: dtype_name.into(),
+                });
+            };
+            let Some(nbytes) = shape
+                .iter()
+                .try_fold(dt.nbytes(), |acc, &d| acc.checked_mul(d))
+            else {
+                return Err(format(format!(
+                    "tensor {name} of {dt} {shape:?} is too large"
+                )));
+            };
+            if start > end || end > data || end - start != nbytes {
+                return Err(format(format!(
+                    "tensor {name} of {dt} {shape:?} has data range {start}..{end} in {data} bytes",
+                )));
+            }
+            if self.tensors.contains_key(&name) {
+                return Err(format(format!("duplicate tensor {name}")));
+            }
+            let range = base + start..base + end;
+            self.tensors.insert(
+                name,
+                TensorInfo {
+                    dt,
+                    shape,
+                    file: id,
+                    range,
+                },
+            );
+        }
+        self.files.push(mmap);
+        Ok(())
+    }
+
+    /// 所有张量的名字和信息，按名字排序。
+    pub fn tensors(&self) -> impl Iterator<Item = (&str, &TensorInfo)> {
+        self.tensors.iter().map(|(name, info)| (&**name, info))
+    }
+
+    /// 名为 `name` 的张量的信息。
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&TensorInfo> {
+        self.tensors.get(name)
+    }
+
+    /// 文件头中的 `__metadata__`，分片模型还包括索引文件中的 `metadata`。
+    #[inline]
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// 名为 `name` 的张量在映射内存中的数据。
+    pub fn data(&self, name: &str) -> Result<&[u8], SafetensorsError> {
+        let info = self.info(name)?;
+        Ok(&self.files[info.file][info.range.clone()])
+    }
+
+    fn info(&self, name: &str) -> Result<&TensorInfo, SafetensorsError> {
+        self.tensors
+            .get(name)
+            .ok_or_else(|| SafetensorsError::NotFound(name.into()))
+    }
+
+    /// 将名为 `name` 的张量同步上传到 `device`。
+    pub fn load(&self, name: &str, device: &Device) -> Result<DevTensor, SafetensorsError> {
+        let info = self.info(name)?;
+        let blob = device.from_host(self.data(name)?);
+        Ok(DevTensor::new(
+            Tensor::contiguous(info.dt, info.shape.iter().copied()),
+            blob,
+        ))
+    }
+
+    /// 在 `stream` 上把所有张量上传到设备。
+    ///
+    /// 数据先复制到两块各 `staging` 字节的锁页内存，再从锁页内存异步复制到设备，
+    /// 两块锁页内存轮流使用，读取映射内存与设备复制可以重叠。`staging` 为 0 时直接从映射内存复制。
+    /// 返回时所有复制都已完成。
+    pub fn load_all(
+        &self,
+        stream: &Stream,
+        staging: usize,
+    ) -> Result<BTreeMap<String, DevTensor>, SafetensorsError> {
+        let device = stream.device();
+        let mut ans = BTreeMap::new();
+
+        if staging == 0 {
+            for (name, info) in &self.tensors {
+                let blob = stream.from_host(&self.files[info.file][info.range.clone()]);
+                let desc = Tensor::contiguous(info.dt, info.shape.iter().copied());
+                ans.insert(name.clone(), DevTensor::new(desc, blob));
+            }
+            stream.synchronize();
+            return Ok(ans);
+        }
+
+        let mut buffers = [
+            (device.malloc_host::<u8>(staging), device.event(), false),
+            (device.malloc_host::<u8>(staging), device.event(), false),
+        ];
+        let mut next = 0;
+        for (name, info) in &self.tensors {
+            let src = &self.files[info.file][info.range.clone()];
+            let mut blob = stream.malloc(src.len());
+            for (dst, src) in blob.chunks_mut(staging).zip(src.chunks(staging)) {
+                let (host, event, pending) = &mut buffers[next];
+                // 等待这块锁页内存上一次的复制完成后再覆盖
+                if *pending {
+                    event.synchronize()
+                }
+                host[..src.len()].copy_from_slice(src);
+                stream.memcpy_h2d(dst, &host[..src.len()]);
+                stream.record(event);
+                *pending = true;
+                next ^= 1;
+            }
+            let desc = Tensor::contiguous(info.dt, info.shape.iter().copied());
+            ans.insert(name.clone(), DevTensor::new(desc, blob));
+        }
+        stream.synchronize();
+        Ok(ans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Safetensors, SafetensorsError};
+    use digit_layout::types;
+    use std::{fs, path::PathBuf};
+
+    /// 测试专用的临时目录。
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "infinicore-safetensors-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 按格式拼出 `.safetensors` 文件：8 字节头长度、JSON 头、数据区。
+    fn write(path: &PathBuf, header: &str, data: &[u8]) {
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        fs::write(path, bytes).unwrap()
+    }
+
+    #[test]
+    fn single_file() {
+        let dir = temp_dir("single");
+        let path = dir.join("model.safetensors");
+        let data = (0..12u8).collect::<Vec<_>>();
+        write(
+            &path,
+            r#"{"__metadata__":{"format":"pt"},"a":{"dtype":"F32","shape":[2],"data_offsets":[0,8]},"b":{"dtype":"U8","shape":[2,2],"data_offsets":[8,12]}}"#,
+            &data,
+        );
+
+        let st = Safetensors::open(&path).unwrap();
+        assert_eq!(
+            st.tensors().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        let a = st.get("a").unwrap();
+        assert_eq!((a.dt, &*a.shape, a.nbytes()), (types::F32, &[2][..], 8));
+        assert_eq!(st.data("a").unwrap(), &data[..8]);
+        assert_eq!(st.data("b").unwrap(), &data[8..]);
+        assert_eq!(st.metadata()["format"], "pt");
+        assert!(matches!(st.data("c"), Err(SafetensorsError::NotFound(_))));
+
+        fs::remove_dir_all(dir).unwrap()
+    }
+
+    #[test]
+    fn sharded() {
+        let dir = temp_dir("sharded");
+        write(
+            &dir.join("model-00001-of-00002.safetensors"),
+            r#"{"a":{"dtype":"F16","shape":[2],"data_offsets":[0,4]}}"#,
+            &[1, 2, 3, 4],
+        );
+        write(
+            &dir.join("model-00002-of-00002.safetensors"),
+            r#"{"b":{"dtype":"I8","shape":[3],"data_offsets":[0,3]}}"#,
+            &[5, 6, 7],
+        );
+        let index = dir.join("model.safetensors.index.json");
+        fs::write(
+            &index,
+            r#"{"metadata":{"total_size":7},"weight_map":{"a":"model-00001-of-00002.safetensors","b":"model-00002-of-00002.safetensors"}}"#,
+        )
+        .unwrap();
+
+        let st = Safetensors::open(&index).unwrap();
+        assert_eq!(st.tensors().count(), 2);
+        assert_eq!(st.get("a").unwrap().dt, types::F16);
+        assert_eq!(st.get("b").unwrap().shape, [3]);
+        assert_eq!(st.data("a").unwrap(), [1, 2, 3, 4]);
+        assert_eq!(st.data("b").unwrap(), [5, 6, 7]);
+        assert_eq!(st.metadata()["total_size"], "7");
+
+        // 索引中的张量不在分片里
+        fs::write(
+            &index,
+            r#"{"weight_map":{"a":"model-00001-of-00002.safetensors","c":"model-00001-of-00002.safetensors"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Safetensors::open(&index),
+            Err(SafetensorsError::Format { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap()
+    }
+
+    #[test]
+    fn bad_header() {
+        let dir = temp_dir("bad");
+        let path = dir.join("model.safetensors");
+        let open = |header: &str| {
+            write(&path, header, &[0; 8]);
+            Safetensors::open(&path)
+        };
+
+        // 元素数乘以元素大小溢出
+        let huge = format!(
+            r#"{{"a":{{"dtype":"F32","shape":[{},4],"data_offsets":[0,8]}}}}"#,
+            usize::MAX / 2
+        );
+        assert!(matches!(open(&huge), Err(SafetensorsError::Format { .. })));
+        // 数据范围与形状不符
+        assert!(matches!(
+            open(r#"{"a":{"dtype":"F32","shape":[3],"data_offsets":[0,8]}}"#),
+            Err(SafetensorsError::Format { .. })
+        ));
+        // 数据范围超出文件
+        assert!(matches!(
+            open(r#"{"a":{"dtype":"F32","shape":[4],"data_offsets":[0,16]}}"#),
+            Err(SafetensorsError::Format { .. })
+        ));
+        assert!(matches!(
+            open(r#"{"a":{"dtype":"Q4","shape":[2],"data_offsets":[0,1]}}"#),
+            Err(SafetensorsError::Dtype { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap()
+    }
+}
@@ -1,16 +1,25 @@
-use crate::{AsRaw, Device, bindings::infinirtStream_t};
-use std::ptr::null_mut;
+use crate::{AsRaw, DevBlob, Device, bindings::infinirtStream_t};
+use std::{ptr::null_mut, sync::Mutex};
 
 /// 一个 InfiniCore 计算流。
-#[repr(transparent)]
-pub struct Stream(infinirtStream_t);
+pub struct Stream {
+    raw: infinirtStream_t,
+    device: Device,
+    /// 此流上的算子工作空间，参见 [`Workspace`](crate::Workspace)。
+    pub(crate) workspace: Mutex<Option<DevBlob>>,
+}
 
 impl Device {
-    /// 在此设备上创建一个新的计算流。
+    /// 激活此设备并在其上创建一个新的计算流。
     pub fn stream(&self) -> Stream {
+        self.set_device();
         let mut stream = null_mut();
         infini!(infinirtStreamCreate(&mut stream));
-        Stream(stream)
+        Stream {
+            raw: stream,
+            device: *self,
+            workspace: Mutex::new(None),
+        }
     }
 }
 
@@ -19,7 +28,10 @@ unsafe impl Sync for Stream {}
 
 impl Drop for Stream {
     fn drop(&mut self) {
-        infini!(infinirtStreamDestroy(self.0))
+        if let Some(workspace) = self.workspace.get_mut().unwrap().take() {
+            self.free(workspace)
+        }
+        infini!(infinirtStreamDestroy(self.raw))
     }
 }
 
@@ -27,7 +39,7 @@ impl AsRaw for Stream {
     type Raw = infinirtStream_t;
     #[inline]
     unsafe fn as_raw(&self) -> Self::Raw {
-        self.0
+        self.raw
     }
 }
 
@@ -35,15 +47,18 @@ impl Stream {
     /// 等待此流中所有先前提交的任务完成。
     #[inline]
     pub fn synchronize(&self) {
-        infini!(infinirtStreamSynchronize(self.0))
+        infini!(infinirtStreamSynchronize(self.raw))
+    }
+
+    /// 此流所属的设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.device
     }
 
     /// 获取与当前 InfiniCore 上下文关联的设备。
     #[inline]
     pub fn get_device(&self) -> Device {
-        let mut ty = crate::infiniDevice_t::INFINI_DEVICE_CPU;
-        let mut id = 0;
-        infini!(infinirtGetDevice(&mut ty, &mut id));
-        Device { ty, id }
+        Device::current()
     }
 }
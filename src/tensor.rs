@@ -1,27 +1,17 @@
-use crate::{AsRaw, bindings::infiniopTensorDescriptor_t, infiniDtype_t};
-use digit_layout::{DigitLayout, types};
-use std::ptr::null_mut;
+use crate::{AsRaw, DevBlob, DevByte, Device, bindings::infiniopTensorDescriptor_t};
+use digit_layout::DigitLayout;
+use std::{fmt, ptr::null_mut};
 
 /// 一个 InfiniCore 张量描述符。
-#[repr(transparent)]
-pub struct Tensor(infiniopTensorDescriptor_t);
-
-fn data_layout(dt: DigitLayout) -> infiniDtype_t {
-    match dt {
-        types::I8 => infiniDtype_t::INFINI_DTYPE_I8,
-        types::I16 => infiniDtype_t::INFINI_DTYPE_I16,
-        types::I32 => infiniDtype_t::INFINI_DTYPE_I32,
-        types::I64 => infiniDtype_t::INFINI_DTYPE_I64,
-        types::U8 => infiniDtype_t::INFINI_DTYPE_U8,
-        types::U16 => infiniDtype_t::INFINI_DTYPE_U16,
-        types::U32 => infiniDtype_t::INFINI_DTYPE_U32,
-        types::U64 => infiniDtype_t::INFINI_DTYPE_U64,
-        types::F16 => infiniDtype_t::INFINI_DTYPE_F16,
-        types::F32 => infiniDtype_t::INFINI_DTYPE_F32,
-        types::F64 => infiniDtype_t::INFINI_DTYPE_F64,
-        types::BF16 => infiniDtype_t::INFINI_DTYPE_BF16,
-        _ => panic!("Unsupported data type: {:?}", dt),
-    }
+///
+/// 除底层描述符外，还记录了数据类型、形状、以字节为单位的步长，
+/// 以及首元素相对于存储起点的字节偏移，以便在不复制数据的情况下构造视图。
+pub struct Tensor {
+    ptr: infiniopTensorDescriptor_t,
+    dt: DigitLayout,
+    shape: Vec<usize>,
+    strides: Vec<isize>,
+    offset: isize,
 }
 
 impl Tensor {
@@ -37,34 +27,364 @@ impl Tensor {
     /// # Panics
     ///
     /// * 如果 `shape` 和 `strides` 的维度数量不匹配。
-    /// * 如果 `dt` 是不支持的数据类型（由 `data_layout` 函数 panic）。
+    /// * 如果 `dt` 无法映射到 `infiniDtype_t`（参见 [`UnsupportedLayout`](crate::UnsupportedLayout)）。
     /// * 如果底层的 `infiniopCreateTensorDescriptor` 调用失败（由 `infini!` 宏 panic）。
     pub fn new(
         dt: DigitLayout,
         shape: impl IntoIterator<Item = usize>,
         strides: impl IntoIterator<Item = isize>,
     ) -> Self {
+        let shape: Vec<_> = shape.into_iter().collect();
+        let strides: Vec<_> = strides.into_iter().collect();
+        assert_eq!(strides.len(), shape.len());
+        Self::with_layout(dt, shape, strides, 0)
+    }
+
+    /// 创建一个行优先连续存储的张量描述符。
+    pub fn contiguous(dt: DigitLayout, shape: impl IntoIterator<Item = usize>) -> Self {
+        let shape: Vec<_> = shape.into_iter().collect();
+        let strides = contiguous_strides(dt, &shape);
+        Self::with_layout(dt, shape, strides, 0)
+    }
+
+    fn with_layout(dt: DigitLayout, shape: Vec<usize>, strides: Vec<isize>, offset: isize) -> Self {
         let ele = dt.nbytes() as isize;
-        let shape: Vec<_> = shape.into_iter().map(|x| x as _).collect();
-        let strides: Vec<_> = strides.into_iter().map(|x| (x / ele) as _).collect();
-        let ndim = shape.len();
-        assert_eq!(strides.len(), ndim);
+        let shape_: Vec<_> = shape.iter().map(|&x| x as _).collect();
+        let strides_: Vec<_> = strides.iter().map(|&x| (x / ele) as _).collect();
 
         let mut ptr = null_mut();
         infini!(infiniopCreateTensorDescriptor(
             &mut ptr,
-            ndim as _,
-            shape.as_ptr(),
-            strides.as_ptr(),
-            data_layout(dt),
+            shape_.len() as _,
+            shape_.as_ptr(),
+            strides_.as_ptr(),
+            infiniDtype_t::try_from(dt).unwrap_or_else(|e| panic!("{e}")),
         ));
-        Self(ptr)
+        Self {
+            ptr,
+            dt,
+            shape,
+            strides,
+            offset,
+        }
+    }
+
+    /// 张量的数据类型。
+    #[inline]
+    pub fn dt(&self) -> DigitLayout {
+        self.dt
+    }
+
+    /// 张量的形状。
+    #[inline]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// 张量的步长（以字节为单位）。
+    #[inline]
+    pub fn strides(&self) -> &[isize] {
+        &self.strides
+    }
+
+    /// 首元素相对于存储起点的字节偏移。
+    #[inline]
+    pub fn offset(&self) -> isize {
+        self.offset
+    }
+
+    /// 张量的维度数。
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// 张量的元素总数。
+    #[inline]
+    pub fn numel(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// 判断张量是否为行优先连续存储。
+    ///
+    /// 长度为 1 的维度不影响连续性。
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = self.dt.nbytes() as isize;
+        for (&d, &s) in self.shape.iter().zip(&self.strides).rev() {
+            if d != 1 && s != expected {
+                return false;
+            }
+            expected *= d as isize;
+        }
+        true
+    }
+
+    /// 张量可能访问到的字节范围，相对于存储起点。
+    ///
+    /// 对于不包含任何元素的张量返回空范围。
+    pub fn byte_range(&self) -> std::ops::Range<isize> {
+        if self.numel() == 0 {
+            return self.offset..self.offset;
+        }
+        let mut start = self.offset;
+        let mut end = self.offset;
+        for (&d, &s) in self.shape.iter().zip(&self.strides) {
+            let span = (d as isize - 1) * s;
+            if span < 0 {
+                start += span;
+            } else {
+                end += span;
+            }
+        }
+        start..end + self.dt.nbytes() as isize
+    }
+
+    /// 按 `order` 重排维度。
+    ///
+    /// # Panics
+    ///
+    /// 如果 `order` 不是 `0..ndim` 的一个排列。
+    pub fn permute(&self, order: &[usize]) -> Self {
+        let ndim = self.ndim();
+        assert_eq!(order.len(), ndim, "permutation length mismatch");
+        let mut seen = vec![false; ndim];
+        for &i in order {
+            assert!(i < ndim && !seen[i], "invalid permutation: {order:?}");
+            seen[i] = true;
+        }
+        let shape = order.iter().map(|&i| self.shape[i]).collect();
+        let strides = order.iter().map(|&i| self.strides[i]).collect();
+        Self::with_layout(self.dt, shape, strides, self.offset)
+    }
+
+    /// 交换两个维度。
+    pub fn transpose(&self, a: usize, b: usize) -> Self {
+        let mut order: Vec<_> = (0..self.ndim()).collect();
+        order.swap(a, b);
+        self.permute(&order)
+    }
+
+    /// 在 `axis` 维度上截取 `[start, start + len)` 区间。
+    ///
+    /// # Panics
+    ///
+    /// 如果区间越界。
+    pub fn narrow(&self, axis: usize, start: usize, len: usize) -> Self {
+        self.slice(axis, start, 1, len)
+    }
+
+    /// 在 `axis` 维度上从 `start` 开始以 `step` 为间隔取 `len` 个元素。
+    ///
+    /// `step` 可以为负数，此时沿该维度反向取元素。
+    ///
+    /// # Panics
+    ///
+    /// * 如果 `step` 为 0。
+    /// * 如果取到的任一元素越界。
+    pub fn slice(&self, axis: usize, start: usize, step: isize, len: usize) -> Self {
+        assert_ne!(step, 0, "slice step must not be zero");
+        let d = self.shape[axis];
+        if len > 0 {
+            let last = start as isize + (len as isize - 1) * step;
+            assert!(
+                start < d && (0..d as isize).contains(&last),
+                "slice out of range: start {start}, step {step}, len {len} on axis of size {d}",
+            );
+        }
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        let offset = self.offset + start as isize * strides[axis];
+        shape[axis] = len;
+        strides[axis] *= step;
+        Self::with_layout(self.dt, shape, strides, offset)
+    }
+
+    /// 按广播规则将张量扩展到 `shape`，扩展出的维度步长为 0。
+    ///
+    /// 形状从最后一维开始对齐，只有长度为 1 的维度可以被扩展，
+    /// 缺少的前导维度视为长度为 1。
+    ///
+    /// # Panics
+    ///
+    /// 如果形状不满足广播规则。
+    pub fn broadcast_to(&self, shape: &[usize]) -> Self {
+        let strides = broadcast_strides(&self.shape, &self.strides, shape)
+            .unwrap_or_else(|| panic!("cannot broadcast {:?} to {shape:?}", self.shape));
+        Self::with_layout(self.dt, shape.to_vec(), strides, self.offset)
+    }
+
+    /// 在 `axis` 处插入一个长度为 1 的维度。
+    pub fn unsqueeze(&self, axis: usize) -> Self {
+        assert!(axis <= self.ndim(), "axis {axis} out of range");
+        let stride = match self.shape.get(axis) {
+            Some(&d) => d as isize * self.strides[axis],
+            None => self.dt.nbytes() as isize,
+        };
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        shape.insert(axis, 1);
+        strides.insert(axis, stride);
+        Self::with_layout(self.dt, shape, strides, self.offset)
+    }
+
+    /// 移除 `axis` 处长度为 1 的维度。
+    ///
+    /// # Panics
+    ///
+    /// 如果该维度长度不为 1。
+    pub fn squeeze(&self, axis: usize) -> Self {
+        assert_eq!(
+            self.shape[axis], 1,
+            "cannot squeeze axis of size {}",
+            self.shape[axis]
+        );
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        shape.remove(axis);
+        strides.remove(axis);
+        Self::with_layout(self.dt, shape, strides, self.offset)
+    }
+
+    /// 将张量变形为 `shape`。
+    ///
+    /// 只有当现有步长能够表达新形状时才会成功；否则返回 [`RearrangeRequired`]，
+    /// 此时需要先将数据整理为连续布局再变形。
+    ///
+    /// # Panics
+    ///
+    /// 如果新旧形状的元素总数不同。
+    pub fn reshape(&self, shape: &[usize]) -> Result<Self, RearrangeRequired> {
+        assert_eq!(
+            shape.iter().product::<usize>(),
+            self.numel(),
+            "cannot reshape {:?} to {shape:?}",
+            self.shape,
+        );
+        let strides = reshape_layout(self.dt, &self.shape, &self.strides, shape)?;
+        Ok(Self::with_layout(
+            self.dt,
+            shape.to_vec(),
+            strides,
+            self.offset,
+        ))
+    }
+}
+
+/// 计算行优先连续存储的字节步长。
+fn contiguous_strides(dt: DigitLayout, shape: &[usize]) -> Vec<isize> {
+    let mut strides = vec![0; shape.len()];
+    let mut acc = dt.nbytes() as isize;
+    for (s, &d) in strides.iter_mut().zip(shape).rev() {
+        *s = acc;
+        acc *= d as isize;
+    }
+    strides
+}
+
+/// 按广播规则计算扩展到 `target` 后的字节步长，扩展出的维度步长为 0。
+///
+/// 形状不满足广播规则时返回 `None`。
+fn broadcast_strides(shape: &[usize], strides: &[isize], target: &[usize]) -> Option<Vec<isize>> {
+    let lead = target.len().checked_sub(shape.len())?;
+    let mut ans = vec![0; target.len()];
+    for (i, (&d, &s)) in shape.iter().zip(strides).enumerate() {
+        match target[lead + i] {
+            t if t == d => ans[lead + i] = s,
+            _ if d == 1 => {}
+            _ => return None,
+        }
+    }
+    Some(ans)
+}
+
+/// 计算变形为 `new_shape` 后的字节步长，调用者保证新旧形状的元素总数相同。
+///
+/// 没有元素或者原张量是标量时任何形状都可以用连续步长表达。
+fn reshape_layout(
+    dt: DigitLayout,
+    shape: &[usize],
+    strides: &[isize],
+    new_shape: &[usize],
+) -> Result<Vec<isize>, RearrangeRequired> {
+    if shape.is_empty() || shape.contains(&0) {
+        Ok(contiguous_strides(dt, new_shape))
+    } else {
+        reshape_strides(shape, strides, new_shape).ok_or(RearrangeRequired)
+    }
+}
+
+/// 尝试在不移动数据的情况下为新形状计算步长。
+///
+/// 将原形状划分为若干段内部连续的维度块，新形状必须能够逐块对应。
+fn reshape_strides(shape: &[usize], strides: &[isize], new_shape: &[usize]) -> Option<Vec<isize>> {
+    let mut new_strides = vec![0; new_shape.len()];
+    let mut view_d = new_shape.len();
+    let mut chunk_base = *strides.last().unwrap();
+    let mut tensor_numel = 1;
+    let mut view_numel = 1;
+    for d in (0..shape.len()).rev() {
+        tensor_numel *= shape[d];
+        let chunk_end =
+            d == 0 || (shape[d - 1] != 1 && strides[d - 1] != tensor_numel as isize * chunk_base);
+        if chunk_end {
+            while view_d > 0 && (view_numel < tensor_numel || new_shape[view_d - 1] == 1) {
+                view_d -= 1;
+                new_strides[view_d] = view_numel as isize * chunk_base;
+                view_numel *= new_shape[view_d];
+            }
+            if view_numel != tensor_numel {
+                return None;
+            }
+            if d > 0 {
+                chunk_base = strides[d - 1];
+                tensor_numel = 1;
+                view_numel = 1;
+            }
+        }
+    }
+    if view_d == 0 { Some(new_strides) } else { None }
+}
+
+/// 变形无法以零拷贝方式完成，需要先用 `rearrange` 将数据整理为连续布局。
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RearrangeRequired;
+
+impl fmt::Display for RearrangeRequired {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "tensor strides do not allow this reshape, rearrange is required"
+        )
+    }
+}
+
+impl std::error::Error for RearrangeRequired {}
+
+impl Clone for Tensor {
+    fn clone(&self) -> Self {
+        Self::with_layout(
+            self.dt,
+            self.shape.clone(),
+            self.strides.clone(),
+            self.offset,
+        )
+    }
+}
+
+impl fmt::Debug for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tensor")
+            .field("dt", &self.dt)
+            .field("shape", &self.shape)
+            .field("strides", &self.strides)
+            .field("offset", &self.offset)
+            .finish()
     }
 }
 
 impl Drop for Tensor {
     fn drop(&mut self) {
-        infini!(infiniopDestroyTensorDescriptor(self.0))
+        infini!(infiniopDestroyTensorDescriptor(self.ptr))
     }
 }
 
@@ -75,6 +395,247 @@ impl AsRaw for Tensor {
     type Raw = infiniopTensorDescriptor_t;
     #[inline]
     unsafe fn as_raw(&self) -> Self::Raw {
-        self.0
+        self.ptr
+    }
+}
+
+/// 一个拥有设备存储的张量，由张量描述符和其引用的设备内存组成。
+///
+/// 视图操作只构造新的描述符，与原张量共享同一块 [`DevBlob`]。
+#[derive(Clone)]
+pub struct DevTensor {
+    desc: Tensor,
+    blob: DevBlob,
+}
+
+impl Device {
+    /// 在设备上分配一个行优先连续存储的张量。
+    pub fn tensor(&self, dt: DigitLayout, shape: impl IntoIterator<Item = usize>) -> DevTensor {
+        let desc = Tensor::contiguous(dt, shape);
+        let blob = self.malloc(desc.numel() * dt.nbytes());
+        DevTensor { desc, blob }
+    }
+}
+
+impl DevTensor {
+    /// 以给定的描述符解释一块设备内存。
+    ///
+    /// # Panics
+    ///
+    /// 如果描述符可能访问到 `blob` 之外的内存。
+    pub fn new(desc: Tensor, blob: DevBlob) -> Self {
+        let range = desc.byte_range();
+        assert!(
+            range.is_empty() || (range.start >= 0 && range.end as usize <= blob.len()),
+            "tensor {desc:?} out of blob of {} bytes",
+            blob.len(),
+        );
+        Self { desc, blob }
+    }
+
+    /// 张量描述符。
+    #[inline]
+    pub fn desc(&self) -> &Tensor {
+        &self.desc
+    }
+
+    /// 张量引用的设备内存。
+    #[inline]
+    pub fn blob(&self) -> &DevBlob {
+        &self.blob
+    }
+
+    /// 取出张量引用的设备内存，例如交给 [`Stream::free`](crate::Stream::free) 异步释放。
+    #[inline]
+    pub fn into_blob(self) -> DevBlob {
+        self.blob
+    }
+
+    /// 张量所在的设备。
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.blob.device()
+    }
+
+    /// 张量首元素的设备地址。
+    #[inline]
+    pub fn as_ptr(&self) -> *const DevByte {
+        self.blob.as_ptr().wrapping_offset(self.desc.offset)
+    }
+
+    /// 张量首元素的可变设备地址。
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut DevByte {
+        self.blob.as_mut_ptr().wrapping_offset(self.desc.offset)
+    }
+
+    fn map(&self, f: impl FnOnce(&Tensor) -> Tensor) -> Self {
+        Self {
+            desc: f(&self.desc),
+            blob: self.blob.clone(),
+        }
+    }
+
+    /// 参见 [`Tensor::permute`]。
+    pub fn permute(&self, order: &[usize]) -> Self {
+        self.map(|t| t.permute(order))
+    }
+
+    /// 参见 [`Tensor::transpose`]。
+    pub fn transpose(&self, a: usize, b: usize) -> Self {
+        self.map(|t| t.transpose(a, b))
+    }
+
+    /// 参见 [`Tensor::narrow`]。
+    pub fn narrow(&self, axis: usize, start: usize, len: usize) -> Self {
+        self.map(|t| t.narrow(axis, start, len))
+    }
+
+    /// 参见 [`Tensor::slice`]。
+    pub fn slice(&self, axis: usize, start: usize, step: isize, len: usize) -> Self {
+        self.map(|t| t.slice(axis, start, step, len))
+    }
+
+    /// 参见 [`Tensor::broadcast_to`]。
+    pub fn broadcast_to(&self, shape: &[usize]) -> Self {
+        self.map(|t| t.broadcast_to(shape))
+    }
+
+    /// 参见 [`Tensor::unsqueeze`]。
+    pub fn unsqueeze(&self, axis: usize) -> Self {
+        self.map(|t| t.unsqueeze(axis))
+    }
+
+    /// 参见 [`Tensor::squeeze`]。
+    pub fn squeeze(&self, axis: usize) -> Self {
+        self.map(|t| t.squeeze(axis))
+    }
+
+    /// 参见 [`Tensor::reshape`]。
+    pub fn reshape(&self, shape: &[usize]) -> Result<Self, RearrangeRequired> {
+        Ok(Self {
+            desc: self.desc.reshape(shape)?,
+            blob: self.blob.clone(),
+        })
+    }
+}
+
+impl std::ops::Deref for DevTensor {
+    type Target = Tensor;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.desc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use digit_layout::types;
+
+    #[test]
+    fn contiguous() {
+        assert_eq!(contiguous_strides(types::F32, &[2, 3, 4]), [48, 16, 4]);
+        assert_eq!(contiguous_strides(types::F16, &[5]), [2]);
+        assert_eq!(contiguous_strides(types::F32, &[]), [0isize; 0]);
+    }
+
+    #[test]
+    fn reshape_merge() {
+        // [2, 3, 4] -> [6, 4] -> [24]
+        let strides = contiguous_strides(types::F32, &[2, 3, 4]);
+        assert_eq!(
+            reshape_strides(&[2, 3, 4], &strides, &[6, 4]),
+            Some(vec![16, 4])
+        );
+        assert_eq!(reshape_strides(&[2, 3, 4], &strides, &[24]), Some(vec![4]));
+    }
+
+    #[test]
+    fn reshape_split() {
+        // [24] -> [2, 3, 4]，长度为 1 的维度可以插在任意位置
+        assert_eq!(
+            reshape_strides(&[24], &[4], &[2, 3, 4]),
+            Some(vec![48, 16, 4])
+        );
+        assert_eq!(
+            reshape_strides(&[6, 4], &[16, 4], &[1, 6, 1, 4]),
+            Some(vec![96, 16, 16, 4]),
+        );
+        assert_eq!(reshape_strides(&[1, 1], &[4, 4], &[]), Some(vec![]));
+    }
+
+    #[test]
+    fn reshape_non_contiguous() {
+        // [4, 6] 的转置 [6, 4]，步长 [4, 24]
+        let strides = [4, 24];
+        // 不能合并
+        assert_eq!(reshape_strides(&[6, 4], &strides, &[24]), None);
+        // 可以在块内拆分
+        assert_eq!(
+            reshape_strides(&[6, 4], &strides, &[2, 3, 4]),
+            Some(vec![12, 4, 24]),
+        );
+        assert_eq!(
+            reshape_strides(&[6, 4], &strides, &[6, 2, 2]),
+            Some(vec![4, 48, 24]),
+        );
+        // 跨块拆分不能表达
+        assert_eq!(reshape_strides(&[6, 4], &strides, &[3, 8]), None);
+
+        // [4, 8] 取前 4 列得到 [4, 4]，步长 [32, 4]，行间不连续
+        assert_eq!(
+            reshape_strides(&[4, 4], &[32, 4], &[2, 2, 4]),
+            Some(vec![64, 32, 4])
+        );
+        assert_eq!(reshape_strides(&[4, 4], &[32, 4], &[16]), None);
+    }
+
+    #[test]
+    fn reshape_zero_strides() {
+        // [3, 1] 广播到 [3, 4]
+        let strides = [4, 0];
+        assert_eq!(
+            reshape_strides(&[3, 4], &strides, &[3, 2, 2]),
+            Some(vec![4, 0, 0])
+        );
+        assert_eq!(reshape_strides(&[3, 4], &strides, &[12]), None);
+        // 整个张量都是广播出来的
+        assert_eq!(reshape_strides(&[3, 4], &[0, 0], &[12]), Some(vec![0]));
+    }
+
+    #[test]
+    fn reshape_degenerate() {
+        // 空张量和标量用连续步长
+        assert_eq!(
+            reshape_layout(types::F32, &[0, 3], &[4, 0], &[3, 0]),
+            Ok(vec![0, 4]),
+        );
+        assert_eq!(
+            reshape_layout(types::F32, &[], &[], &[1, 1]),
+            Ok(vec![4, 4])
+        );
+        assert_eq!(
+            reshape_layout(types::F32, &[6, 4], &[4, 24], &[24]),
+            Err(RearrangeRequired),
+        );
+    }
+
+    #[test]
+    fn broadcast() {
+        assert_eq!(
+            broadcast_strides(&[3, 1], &[4, 4], &[3, 5]),
+            Some(vec![4, 0])
+        );
+        assert_eq!(
+            broadcast_strides(&[4], &[4], &[2, 3, 4]),
+            Some(vec![0, 0, 4])
+        );
+        assert_eq!(broadcast_strides(&[], &[], &[2]), Some(vec![0]));
+        assert_eq!(broadcast_strides(&[3], &[4], &[3]), Some(vec![4]));
+        // 只有长度为 1 的维度可以扩展
+        assert_eq!(broadcast_strides(&[3], &[4], &[6]), None);
+        // 不能减少维度
+        assert_eq!(broadcast_strides(&[2, 3], &[12, 4], &[3]), None);
     }
 }
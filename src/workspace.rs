@@ -0,0 +1,45 @@
+use crate::{DevBlob, DevByte, Stream};
+use std::sync::MutexGuard;
+
+/// 绑定到 [`Stream`] 的算子工作空间。
+///
+/// 每个流持有一块工作空间内存，按需增长到历史最大的请求大小，之后的启动复用同一块内存，
+/// 因此稳态下的算子启动不再分配工作空间。增长时旧内存在流上异步释放，与已排队的计算保持顺序。
+///
+/// 持有 `Workspace` 期间流的工作空间被锁定，同一线程不应再次调用 [`Stream::workspace`]。
+pub struct Workspace<'s> {
+    stream: &'s Stream,
+    blob: MutexGuard<'s, Option<DevBlob>>,
+}
+
+impl Stream {
+    /// 锁定并获取此流的工作空间。
+    pub fn workspace(&self) -> Workspace<'_> {
+        Workspace {
+            stream: self,
+            blob: self.workspace.lock().unwrap(),
+        }
+    }
+}
+
+impl Workspace<'_> {
+    /// 工作空间当前的容量（字节）。
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.blob.as_ref().map_or(0, |blob| blob.len())
+    }
+
+    /// 取出 `nbytes` 字节的工作空间，容量不足时先增长到 `nbytes`。
+    pub fn get(&mut self, nbytes: usize) -> &mut [DevByte] {
+        if self.capacity() < nbytes {
+            if let Some(old) = self.blob.take() {
+                self.stream.free(old)
+            }
+            *self.blob = Some(self.stream.malloc(nbytes))
+        }
+        match &mut *self.blob {
+            Some(blob) => &mut blob[..nbytes],
+            None => &mut [],
+        }
+    }
+}
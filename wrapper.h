@@ -1,2 +1,5 @@
 #include "infinirt.h"
-#include "infiniop.h"
\ No newline at end of file
+#include "infiniop.h"
+#ifdef INFINI_CCL
+#include "infiniccl.h"
+#endif